@@ -0,0 +1,227 @@
+use std::fmt::{self, Display, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// A value of one of two possible types.
+///
+/// This is used by combinators that send a part to one of two writers, where
+/// the value returned by sending the part is from one writer or the other.
+///
+/// `Either` is also a `MultipartWrite` when both variants are writers with the
+/// same associated types, which allows choosing a writer at runtime without
+/// boxing it.
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use std::collections::VecDeque;
+///
+/// use multipart_write::{Either, MultipartWriteExt as _, write};
+///
+/// let in_memory = true;
+/// let mut writer = if in_memory {
+///     Either::Left(write::extend(Vec::new()))
+/// } else {
+///     Either::Right(write::extend(VecDeque::new()).map_ok(Vec::from))
+/// };
+///
+/// writer.send_flush(1).await.unwrap();
+/// writer.send_flush(2).await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!(out, vec![1, 2]);
+/// # })
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Either<L, R> {
+    /// A value of the left type.
+    Left(L),
+    /// A value of the right type.
+    Right(R),
+}
+
+impl<L, R> Either<L, R> {
+    /// Returns `true` if this is the `Left` variant.
+    pub fn is_left(&self) -> bool {
+        matches!(self, Self::Left(_))
+    }
+
+    /// Returns `true` if this is the `Right` variant.
+    pub fn is_right(&self) -> bool {
+        matches!(self, Self::Right(_))
+    }
+
+    /// Returns the left value if this is the `Left` variant.
+    pub fn left(self) -> Option<L> {
+        match self {
+            Self::Left(l) => Some(l),
+            Self::Right(_) => None,
+        }
+    }
+
+    /// Returns the right value if this is the `Right` variant.
+    pub fn right(self) -> Option<R> {
+        match self {
+            Self::Left(_) => None,
+            Self::Right(r) => Some(r),
+        }
+    }
+
+    /// Converts from `&Either<L, R>` to `Either<&L, &R>`.
+    pub fn as_ref(&self) -> Either<&L, &R> {
+        match self {
+            Self::Left(l) => Either::Left(l),
+            Self::Right(r) => Either::Right(r),
+        }
+    }
+
+    /// Converts from `&mut Either<L, R>` to `Either<&mut L, &mut R>`.
+    pub fn as_mut(&mut self) -> Either<&mut L, &mut R> {
+        match self {
+            Self::Left(l) => Either::Left(l),
+            Self::Right(r) => Either::Right(r),
+        }
+    }
+
+    /// Converts from `Pin<&mut Either<L, R>>` to `Either<Pin<&mut L>, Pin<&mut
+    /// R>>`.
+    pub fn as_pin_mut(
+        self: Pin<&mut Self>,
+    ) -> Either<Pin<&mut L>, Pin<&mut R>> {
+        // SAFETY: The value in either variant is structurally pinned; it is
+        // never moved out of the pinned `Either`.
+        unsafe {
+            match self.get_unchecked_mut() {
+                Self::Left(l) => Either::Left(Pin::new_unchecked(l)),
+                Self::Right(r) => Either::Right(Pin::new_unchecked(r)),
+            }
+        }
+    }
+
+    /// Apply one of two functions depending on the variant.
+    pub fn either<T>(
+        self,
+        f: impl FnOnce(L) -> T,
+        g: impl FnOnce(R) -> T,
+    ) -> T {
+        match self {
+            Self::Left(l) => f(l),
+            Self::Right(r) => g(r),
+        }
+    }
+}
+
+impl<T> Either<T, T> {
+    /// Returns the value contained in either variant.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Left(t) | Self::Right(t) => t,
+        }
+    }
+}
+
+impl<L: Display, R: Display> Display for Either<L, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left(l) => l.fmt(f),
+            Self::Right(r) => r.fmt(f),
+        }
+    }
+}
+
+impl<L, R, Part> FusedMultipartWrite<Part> for Either<L, R>
+where
+    L: FusedMultipartWrite<Part>,
+    R: FusedMultipartWrite<
+            Part,
+            Recv = L::Recv,
+            Output = L::Output,
+            Error = L::Error,
+        >,
+{
+    fn is_terminated(&self) -> bool {
+        match self {
+            Self::Left(l) => l.is_terminated(),
+            Self::Right(r) => r.is_terminated(),
+        }
+    }
+}
+
+impl<L, R, Part> MultipartWrite<Part> for Either<L, R>
+where
+    L: MultipartWrite<Part>,
+    R: MultipartWrite<
+            Part,
+            Recv = L::Recv,
+            Output = L::Output,
+            Error = L::Error,
+        >,
+{
+    type Error = L::Error;
+    type Output = L::Output;
+    type Recv = L::Recv;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        match self.as_pin_mut() {
+            Either::Left(l) => l.poll_ready(cx),
+            Either::Right(r) => r.poll_ready(cx),
+        }
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        match self.as_pin_mut() {
+            Either::Left(l) => l.start_send(part),
+            Either::Right(r) => r.start_send(part),
+        }
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        match self.as_pin_mut() {
+            Either::Left(l) => l.poll_flush(cx),
+            Either::Right(r) => r.poll_flush(cx),
+        }
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        match self.as_pin_mut() {
+            Either::Left(l) => l.poll_complete(cx),
+            Either::Right(r) => r.poll_complete(cx),
+        }
+    }
+}
+
+impl<L, R, Part> AbortMultipartWrite<Part> for Either<L, R>
+where
+    L: AbortMultipartWrite<Part>,
+    R: AbortMultipartWrite<
+            Part,
+            Recv = L::Recv,
+            Output = L::Output,
+            Error = L::Error,
+        >,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        match self.as_pin_mut() {
+            Either::Left(l) => l.poll_abort(cx),
+            Either::Right(r) => r.poll_abort(cx),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //! Foreign writer types.
 //!
 //! This module implements `MultipartWrite` for the `tokio` and `std` writer
-//! types, and it exports constructors for creating these implementations.
+//! types, and it exports constructors for creating these implementations.  It
+//! also has writers of parts of bytes.
 use std::io::Write;
 
 #[cfg(feature = "tokio")]
@@ -14,6 +15,13 @@ pub use multi_async_writer::{MultiAsyncWriter, async_writer};
 mod multi_io_writer;
 pub use multi_io_writer::MultiIoWriter;
 
+#[cfg(feature = "bytes")]
+mod rechunk;
+#[cfg(feature = "bytes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+#[doc(inline)]
+pub use rechunk::{Rechunk, rechunk};
+
 /// Constructs a `MultipartWrite` from an `std::io::Write`.
 pub fn io_writer<W: Write + Default>(write: W) -> MultiIoWriter<W> {
     MultiIoWriter::new(write)
@@ -1,4 +1,4 @@
-use crate::MultipartWrite;
+use crate::{AbortMultipartWrite, MultipartWrite};
 
 use std::pin::Pin;
 use std::task::{self, Context, Poll};
@@ -101,3 +101,19 @@ impl<W: AsyncWrite + Default + Unpin> MultipartWrite<&[u8]>
         Poll::Ready(Ok(std::mem::take(&mut self.inner)))
     }
 }
+
+impl<W: AsyncWrite + Default + Unpin> AbortMultipartWrite<&[u8]>
+    for MultiAsyncWriter<W>
+{
+    fn poll_abort(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // What was written cannot be unwritten, so the best that can be done
+        // is to throw it away along with anything still buffered.
+        self.buf.clear();
+        self.written = 0;
+        self.inner = W::default();
+        Poll::Ready(Ok(()))
+    }
+}
@@ -1,4 +1,4 @@
-use crate::MultipartWrite;
+use crate::{AbortMultipartWrite, MultipartWrite};
 
 use std::io::Write;
 use std::pin::Pin;
@@ -51,3 +51,15 @@ impl<W: Write + Default> MultipartWrite<&[u8]> for MultiIoWriter<W> {
         Poll::Ready(Ok(std::mem::take(&mut self.inner)))
     }
 }
+
+impl<W: Write + Default> AbortMultipartWrite<&[u8]> for MultiIoWriter<W> {
+    fn poll_abort(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // What was written cannot be unwritten, so the best that can be done
+        // is to throw it away.
+        self.inner = W::default();
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Bytes, BytesMut};
+use futures_core::ready;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// Returns a `MultipartWrite` that merges and splits parts of bytes so that
+/// every part sent to `writer` is at least `min` and at most `max` bytes.
+///
+/// Bytes are buffered until there are at least `min` of them, and then up to
+/// `max` bytes are sent to the inner writer as one part.  The last part, which
+/// is sent when the writer is completed, is the only one that can be smaller
+/// than `min`.  For that reason, flushing the writer only sends the parts that
+/// are within bounds, and the rest stay buffered.
+///
+/// A part that is split is not copied, and bytes are only copied to merge
+/// several parts into one.
+///
+/// The value returned by sending a part is the list of values that the inner
+/// writer returned for the parts sent to it since the last part was sent.
+///
+/// # Panics
+///
+/// Panics if `min` is zero or greater than `max`.
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use bytes::Bytes;
+/// use multipart_write::{MultipartWriteExt as _, io};
+///
+/// let parts: Vec<Bytes> = Vec::new();
+/// let mut writer = io::rechunk(4, 6, parts);
+///
+/// writer.feed(Bytes::from_static(b"ab")).await.unwrap();
+/// writer.feed(Bytes::from_static(b"cdefghijk")).await.unwrap();
+/// writer.feed(Bytes::from_static(b"lm")).await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!(out, vec!["abcdef", "ghijk", "lm"]);
+/// # })
+/// ```
+pub fn rechunk<Wr>(min: usize, max: usize, writer: Wr) -> Rechunk<Wr>
+where
+    Wr: MultipartWrite<Bytes>,
+{
+    Rechunk::new(min, max, writer)
+}
+
+pin_project_lite::pin_project! {
+    /// The writer returned by [`rechunk`].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Rechunk<Wr: MultipartWrite<Bytes>> {
+        #[pin]
+        writer: Wr,
+        min: usize,
+        max: usize,
+        target: usize,
+        part_limit: Option<usize>,
+        next_threshold: usize,
+        parts: usize,
+        buf: VecDeque<Bytes>,
+        buffered: usize,
+        recv: Vec<Wr::Recv>,
+    }
+}
+
+impl<Wr: MultipartWrite<Bytes>> Rechunk<Wr> {
+    fn new(min: usize, max: usize, writer: Wr) -> Self {
+        assert!(min > 0, "minimum part size must be non-zero");
+        assert!(min <= max, "minimum part size must not exceed the maximum");
+        Self {
+            writer,
+            min,
+            max,
+            target: min,
+            part_limit: None,
+            next_threshold: usize::MAX,
+            parts: 0,
+            buf: VecDeque::new(),
+            buffered: 0,
+            recv: Vec::new(),
+        }
+    }
+
+    /// Grow the size of the parts as the number of parts sent approaches
+    /// `part_limit`.
+    ///
+    /// Parts start out at the minimum size, and the size is doubled, up to the
+    /// maximum size, each time half of the remaining parts have been used.
+    /// This keeps parts small for small writes while making it less likely
+    /// that a large write runs out of parts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `part_limit` is zero.
+    pub fn adaptive(self, part_limit: usize) -> Self {
+        assert!(part_limit > 0, "part limit must be non-zero");
+        Self {
+            part_limit: Some(part_limit),
+            next_threshold: part_limit / 2,
+            ..self
+        }
+    }
+
+    /// Returns the size of the parts currently being sent.
+    pub fn part_size(&self) -> usize {
+        self.target
+    }
+
+    /// Returns the number of parts sent to the inner writer since it was last
+    /// completed.
+    pub fn parts_sent(&self) -> usize {
+        self.parts
+    }
+
+    /// Returns the number of bytes buffered and not yet sent.
+    pub fn buffered_len(&self) -> usize {
+        self.buffered
+    }
+
+    /// Consumes `Rechunk`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Send parts to the inner writer while there are at least `len` bytes
+    /// buffered.
+    fn poll_send_parts(
+        self: Pin<&mut Self>,
+        len: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Wr::Error>> {
+        let mut this = self.project();
+        while *this.buffered > 0 && *this.buffered >= len {
+            ready!(this.writer.as_mut().poll_ready(cx))?;
+            let n = (*this.buffered).min(*this.max);
+            let part = split_to(this.buf, n);
+            *this.buffered -= n;
+            this.recv.push(this.writer.as_mut().start_send(part)?);
+            *this.parts += 1;
+            if *this.parts >= *this.next_threshold {
+                let limit = this.part_limit.unwrap_or_default();
+                let remaining = limit.saturating_sub(*this.parts);
+                *this.target = (*this.target).saturating_mul(2).min(*this.max);
+                *this.next_threshold = *this.parts + remaining.div_ceil(2);
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Reset the part sizes for the next write.
+    fn reset(self: Pin<&mut Self>) {
+        let this = self.project();
+        *this.target = *this.min;
+        *this.parts = 0;
+        *this.next_threshold = this.part_limit.map_or(usize::MAX, |n| n / 2);
+        this.recv.clear();
+    }
+}
+
+impl<Wr: FusedMultipartWrite<Bytes>> FusedMultipartWrite<Bytes>
+    for Rechunk<Wr>
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr: MultipartWrite<Bytes>> MultipartWrite<Bytes> for Rechunk<Wr> {
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Vec<Wr::Recv>;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let len = self.target;
+        self.poll_send_parts(len, cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Bytes,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        if !part.is_empty() {
+            *this.buffered += part.len();
+            this.buf.push_back(part);
+        }
+        Ok(std::mem::take(this.recv))
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let len = self.min;
+        ready!(self.as_mut().poll_send_parts(len, cx))?;
+        self.project().writer.poll_flush(cx)
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        ready!(self.as_mut().poll_send_parts(0, cx))?;
+        let out = ready!(self.as_mut().project().writer.poll_complete(cx));
+        self.reset();
+        Poll::Ready(out)
+    }
+}
+
+impl<Wr: AbortMultipartWrite<Bytes>> AbortMultipartWrite<Bytes>
+    for Rechunk<Wr>
+{
+    fn poll_abort(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.as_mut().project();
+        this.buf.clear();
+        *this.buffered = 0;
+        let res = ready!(self.as_mut().project().writer.poll_abort(cx));
+        self.reset();
+        Poll::Ready(res)
+    }
+}
+
+impl<Wr> Debug for Rechunk<Wr>
+where
+    Wr: MultipartWrite<Bytes> + Debug,
+    Wr::Recv: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rechunk")
+            .field("writer", &self.writer)
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .field("target", &self.target)
+            .field("part_limit", &self.part_limit)
+            .field("parts", &self.parts)
+            .field("buf", &self.buf)
+            .field("buffered", &self.buffered)
+            .field("recv", &self.recv)
+            .finish()
+    }
+}
+
+/// Take the first `n` bytes of the buffered parts, which must hold at least
+/// that many.
+fn split_to(buf: &mut VecDeque<Bytes>, n: usize) -> Bytes {
+    let front = buf.front_mut().expect("enough bytes are buffered");
+    if front.len() > n {
+        return front.split_to(n);
+    }
+    if front.len() == n {
+        return buf.pop_front().unwrap();
+    }
+    // The part spans more than one buffered part, so they are merged.
+    let mut part = BytesMut::with_capacity(n);
+    while part.len() < n {
+        let front = buf.front_mut().expect("enough bytes are buffered");
+        let m = front.len().min(n - part.len());
+        part.extend_from_slice(&front.split_to(m));
+        if front.is_empty() {
+            buf.pop_front();
+        }
+    }
+    part.freeze()
+}
@@ -28,16 +28,28 @@
 //! 4. Transform a stream by writing it in parts, which is somewhat of a
 //!    specific rephrasing of the second and third points.
 //!
+//! # Features
+//!
+//! * `bytes`: Writers of `bytes::Bytes`, such as one that enforces bounds on
+//!   the size of each part.
+//! * `futures-sink`: Conversions between `MultipartWrite` and `Sink`.
+//! * `tokio`: `MultipartWrite` for `tokio::io::AsyncWrite` and combinators that
+//!   depend on `tokio` timers or on spawning tasks.
+//!
 //! [`Sink`]: https://docs.rs/crate/futures-sink/latest
 //! [example]: https://github.com/quasi-coherent/multipart-write/blob/master/examples/author.rs
 //! [`aws-multipart-upload`]: https://docs.rs/crate/aws-multipart-upload/latest
 #![cfg_attr(docsrs, feature(doc_cfg))]
 use std::collections::VecDeque;
 use std::convert::Infallible as Never;
+use std::future::Future;
 use std::ops::DerefMut;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+mod either;
+pub use either::Either;
+
 pub mod io;
 
 pub mod stream;
@@ -175,6 +187,88 @@ pub type LocalBoxFusedMultipartWrite<'a, Part, R, T, E> = Pin<
     Box<dyn FusedMultipartWrite<Part, Recv = R, Output = T, Error = E> + 'a>,
 >;
 
+/// A writer that can abandon the write in progress.
+///
+/// Aborting is the alternative to completing a writer: instead of assembling
+/// the output from the parts written so far, any buffered parts are discarded
+/// and whatever was already written is cleaned up.  For example, a writer for
+/// a multipart upload would abort the upload so that the parts that were
+/// uploaded are not left behind.
+///
+/// After `poll_abort` returns `Poll::Ready(Ok(()))` the writer is in the same
+/// state it would be in after completing, ready to start a new write if it is
+/// not terminated.  Combinators forward the abort to the writers they wrap.
+pub trait AbortMultipartWrite<Part>: MultipartWrite<Part> {
+    /// Abandon the write, discarding and cleaning up any parts written so
+    /// far.
+    ///
+    /// This method returns `Poll::Pending` until the cleanup has finished.
+    ///
+    /// # Errors
+    ///
+    /// Errors returned by this method are entirely implementation-specific.
+    /// An error means that the cleanup may not have finished.
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>>;
+}
+
+/// A factory for writers, used where a new writer is needed for each output
+/// rather than reusing one writer after it has been completed.
+///
+/// This is implemented for closures `FnMut() -> Fut` where `Fut` is a future
+/// that resolves to a new writer.  A writer that can be created synchronously
+/// can be returned in [`std::future::ready`].
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use multipart_write::{MakeMultipartWrite, MultipartWriteExt as _};
+///
+/// let mut make = || async {
+///     let writer: Vec<u8> = Vec::new();
+///     Ok::<_, std::convert::Infallible>(writer)
+/// };
+///
+/// let mut writer = MakeMultipartWrite::<u8>::make(&mut make).await.unwrap();
+/// writer.send_flush(1).await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!(out, vec![1]);
+/// # })
+/// ```
+pub trait MakeMultipartWrite<Part> {
+    /// The type of writer that this makes.
+    type Writer: MultipartWrite<Part>;
+
+    /// The future that resolves to a new writer.
+    type Future: Future<
+        Output = Result<
+            Self::Writer,
+            <Self::Writer as MultipartWrite<Part>>::Error,
+        >,
+    >;
+
+    /// Start making a new writer.
+    fn make(&mut self) -> Self::Future;
+}
+
+impl<Part, Wr, Fut, F> MakeMultipartWrite<Part> for F
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Wr, Wr::Error>>,
+    Wr: MultipartWrite<Part>,
+{
+    type Future = Fut;
+    type Writer = Wr;
+
+    fn make(&mut self) -> Self::Future {
+        (self)()
+    }
+}
+
 impl<W: ?Sized + MultipartWrite<Part> + Unpin, Part> MultipartWrite<Part>
     for &mut W
 {
@@ -219,6 +313,17 @@ impl<W: ?Sized + FusedMultipartWrite<Part> + Unpin, Part>
     }
 }
 
+impl<W: ?Sized + AbortMultipartWrite<Part> + Unpin, Part>
+    AbortMultipartWrite<Part> for &mut W
+{
+    fn poll_abort(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut **self).poll_abort(cx)
+    }
+}
+
 impl<W: ?Sized + MultipartWrite<Part> + Unpin, Part> MultipartWrite<Part>
     for Box<W>
 {
@@ -263,6 +368,17 @@ impl<W: ?Sized + FusedMultipartWrite<Part> + Unpin, Part>
     }
 }
 
+impl<W: ?Sized + AbortMultipartWrite<Part> + Unpin, Part>
+    AbortMultipartWrite<Part> for Box<W>
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        Pin::new(self.get_mut().as_mut()).poll_abort(cx)
+    }
+}
+
 impl<P, Part> MultipartWrite<Part> for Pin<P>
 where
     P: DerefMut + Unpin,
@@ -311,6 +427,88 @@ where
     }
 }
 
+impl<P, Part> AbortMultipartWrite<Part> for Pin<P>
+where
+    P: DerefMut + Unpin,
+    P::Target: AbortMultipartWrite<Part>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().as_mut().poll_abort(cx)
+    }
+}
+
+impl<W: FusedMultipartWrite<Part>, Part> FusedMultipartWrite<Part>
+    for Option<W>
+{
+    fn is_terminated(&self) -> bool {
+        self.as_ref().is_some_and(|wr| wr.is_terminated())
+    }
+}
+
+/// `None` is a writer that does nothing with the parts it is sent.
+impl<W: MultipartWrite<Part>, Part> MultipartWrite<Part> for Option<W> {
+    type Error = W::Error;
+    type Output = Option<W::Output>;
+    type Recv = Option<W::Recv>;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        match self.as_pin_mut() {
+            Some(wr) => wr.poll_ready(cx),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        match self.as_pin_mut() {
+            Some(wr) => wr.start_send(part).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        match self.as_pin_mut() {
+            Some(wr) => wr.poll_flush(cx),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        match self.as_pin_mut() {
+            Some(wr) => wr.poll_complete(cx).map_ok(Some),
+            _ => Poll::Ready(Ok(None)),
+        }
+    }
+}
+
+impl<W: AbortMultipartWrite<Part>, Part> AbortMultipartWrite<Part>
+    for Option<W>
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        match self.as_pin_mut() {
+            Some(wr) => wr.poll_abort(cx),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+}
+
 impl<T> MultipartWrite<T> for Vec<T> {
     type Error = Never;
     type Output = Self;
@@ -352,6 +550,18 @@ impl<T> MultipartWrite<T> for Vec<T> {
     }
 }
 
+impl<T> AbortMultipartWrite<T> for Vec<T> {
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // SAFETY: We may treat `Vec<T>: Unpin` since we are not pinning the
+        // elements.
+        unsafe { self.get_unchecked_mut() }.clear();
+        Poll::Ready(Ok(()))
+    }
+}
+
 impl<T> MultipartWrite<T> for VecDeque<T> {
     type Error = Never;
     type Output = Self;
@@ -392,3 +602,15 @@ impl<T> MultipartWrite<T> for VecDeque<T> {
         Poll::Ready(Ok(out))
     }
 }
+
+impl<T> AbortMultipartWrite<T> for VecDeque<T> {
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // SAFETY: We may treat `VecDeque<T>: Unpin` since we are not pinning
+        // the elements.
+        unsafe { self.get_unchecked_mut() }.clear();
+        Poll::Ready(Ok(()))
+    }
+}
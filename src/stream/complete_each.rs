@@ -0,0 +1,148 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+use futures_core::stream::{FusedStream, Stream};
+
+use crate::{MakeMultipartWrite, MultipartWrite};
+
+pin_project_lite::pin_project! {
+    /// Stream for [`complete_each`].
+    ///
+    /// [`complete_each`]: super::MultipartStreamExt::complete_each
+    #[must_use = "futures do nothing unless polled"]
+    pub struct CompleteEach<St: Stream, M: MakeMultipartWrite<St::Item>, F> {
+        #[pin]
+        stream: St,
+        make: M,
+        #[pin]
+        making: Option<M::Future>,
+        #[pin]
+        writer: Option<M::Writer>,
+        buffered: Option<St::Item>,
+        f: F,
+        state: State,
+        is_terminated: bool,
+    }
+}
+
+impl<St: Stream, M: MakeMultipartWrite<St::Item>, F> CompleteEach<St, M, F> {
+    pub(super) fn new(stream: St, make: M, f: F) -> Self {
+        Self {
+            stream,
+            make,
+            making: None,
+            writer: None,
+            buffered: None,
+            f,
+            state: State::PollNext,
+            is_terminated: false,
+        }
+    }
+}
+
+impl<St, M, F> FusedStream for CompleteEach<St, M, F>
+where
+    St: Stream,
+    M: MakeMultipartWrite<St::Item>,
+    F: FnMut(<M::Writer as MultipartWrite<St::Item>>::Recv) -> bool,
+{
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl<St, M, F> Stream for CompleteEach<St, M, F>
+where
+    St: Stream,
+    M: MakeMultipartWrite<St::Item>,
+    F: FnMut(<M::Writer as MultipartWrite<St::Item>>::Recv) -> bool,
+{
+    type Item = Result<
+        <M::Writer as MultipartWrite<St::Item>>::Output,
+        <M::Writer as MultipartWrite<St::Item>>::Error,
+    >;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if this.buffered.is_some() {
+                // A writer is only made when there is a part for it, so that
+                // no output is ever completed from an empty writer.
+                if this.writer.is_none() {
+                    if this.making.is_none() {
+                        this.making.set(Some(this.make.make()));
+                    }
+                    let fut = this.making.as_mut().as_pin_mut().unwrap();
+                    let res = ready!(fut.poll(cx));
+                    this.making.set(None);
+                    this.writer.set(Some(res?));
+                }
+                let mut writer = this.writer.as_mut().as_pin_mut().unwrap();
+                ready!(writer.as_mut().poll_ready(cx))?;
+                let it = this.buffered.take().unwrap();
+                let ret = writer.start_send(it)?;
+                if (this.f)(ret) {
+                    *this.state = State::PollComplete(false);
+                }
+            }
+
+            match *this.state {
+                State::PollNext => {
+                    match ready!(this.stream.as_mut().poll_next(cx)) {
+                        Some(it) => *this.buffered = Some(it),
+                        None if this.writer.is_none() => {
+                            *this.is_terminated = true;
+                            return Poll::Ready(None);
+                        },
+                        None => *this.state = State::PollComplete(true),
+                    }
+                },
+                State::PollComplete(last) => {
+                    let writer = this.writer.as_mut().as_pin_mut().unwrap();
+                    let out = ready!(writer.poll_complete(cx));
+                    // The next writer is made when the next part arrives.
+                    this.writer.set(None);
+                    *this.state =
+                        if last { State::Terminated } else { State::PollNext };
+                    return Poll::Ready(Some(out));
+                },
+                State::Terminated => {
+                    *this.is_terminated = true;
+                    return Poll::Ready(None);
+                },
+            }
+        }
+    }
+}
+
+impl<St, M, F> Debug for CompleteEach<St, M, F>
+where
+    St: Stream + Debug,
+    St::Item: Debug,
+    M: MakeMultipartWrite<St::Item> + Debug,
+    M::Writer: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompleteEach")
+            .field("stream", &self.stream)
+            .field("make", &self.make)
+            .field("writer", &self.writer)
+            .field("buffered", &self.buffered)
+            .field("state", &self.state)
+            .field("is_terminated", &self.is_terminated)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    PollNext,
+    PollComplete(bool),
+    Terminated,
+}
@@ -0,0 +1,207 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+use futures_core::stream::Stream;
+
+use crate::FusedMultipartWrite;
+
+/// Decides when the writer of a [`CompleteLoop`] is completed.
+pub(super) trait Trigger<Recv> {
+    /// Called with the value returned by sending a part, returning `true` if
+    /// the writer should be completed now.
+    fn on_sent(&mut self, ret: Recv) -> bool;
+
+    /// Called before the stream is polled for the next item, returning `true`
+    /// if the writer should be completed now.  `empty` is `true` when nothing
+    /// has been written since the writer was last completed.
+    fn poll_expired(&mut self, _empty: bool, _cx: &mut Context<'_>) -> bool {
+        false
+    }
+
+    /// Called after the writer has been completed.
+    fn on_complete(&mut self) {}
+}
+
+/// Completes the writer when the closure returns `true` for the value
+/// returned by sending a part.
+pub(super) struct When<F>(pub(super) F);
+
+impl<F: FnMut(Recv) -> bool, Recv> Trigger<Recv> for When<F> {
+    fn on_sent(&mut self, ret: Recv) -> bool {
+        (self.0)(ret)
+    }
+}
+
+impl<F> Debug for When<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("When").finish_non_exhaustive()
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The loop shared by the streams that write items to one writer and
+    /// complete it whenever a [`Trigger`] says so.
+    pub(super) struct CompleteLoop<St: Stream, Wr, T> {
+        #[pin]
+        stream: St,
+        #[pin]
+        writer: Wr,
+        buffered: Option<St::Item>,
+        trigger: T,
+        state: State,
+        empty: bool,
+        is_terminated: bool,
+    }
+}
+
+impl<St: Stream, Wr, T> CompleteLoop<St, Wr, T> {
+    pub(super) fn new(stream: St, writer: Wr, trigger: T) -> Self {
+        Self {
+            stream,
+            writer,
+            buffered: None,
+            trigger,
+            state: State::PollNext,
+            empty: true,
+            is_terminated: false,
+        }
+    }
+
+    pub(super) fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+
+    pub(super) fn writer_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Drop the item that could not be written and start a new output.
+    pub(super) fn discard(self: Pin<&mut Self>) {
+        let this = self.project();
+        *this.buffered = None;
+        *this.empty = true;
+    }
+
+    pub(super) fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Wr::Output, Wr::Error>>>
+    where
+        Wr: FusedMultipartWrite<St::Item>,
+        T: Trigger<Wr::Recv>,
+    {
+        let mut this = self.project();
+
+        loop {
+            // Try to send anything in the buffer first.
+            if this.buffered.is_some() {
+                let res = match ready!(this.writer.as_mut().poll_ready(cx)) {
+                    Ok(()) => {
+                        let it = this.buffered.take().unwrap();
+                        this.writer.as_mut().start_send(it)
+                    },
+                    Err(e) => Err(e),
+                };
+                match res {
+                    Err(e) => {
+                        // A writer that is terminated by the error cannot be
+                        // written to again, so end on the next poll.
+                        if this.writer.is_terminated() {
+                            *this.buffered = None;
+                            *this.state = State::Terminated;
+                        }
+                        return Poll::Ready(Some(Err(e)));
+                    },
+                    Ok(ret) => {
+                        *this.empty = false;
+                        // Check if we should complete according to the
+                        // trigger.
+                        if this.trigger.on_sent(ret) {
+                            // `poll_complete` not followed by stream shutdown:
+                            // the state is `PollComplete(true)` only when the
+                            // stream stopped producing but we have to do one
+                            // final call to `poll_complete` because it has had
+                            // something written to it
+                            *this.state = State::PollComplete(false);
+                        } else {
+                            *this.state = State::PollNext;
+                        }
+                    },
+                }
+            }
+
+            match *this.state {
+                State::PollNext => {
+                    if this.trigger.poll_expired(*this.empty, cx) {
+                        *this.state = State::PollComplete(false);
+                        continue;
+                    }
+                    match ready!(this.stream.as_mut().poll_next(cx)) {
+                        Some(it) => *this.buffered = Some(it),
+                        _ => {
+                            // No more stream and nothing written to this writer
+                            // means it's over.
+                            if *this.empty {
+                                *this.is_terminated = true;
+                                return Poll::Ready(None);
+                            }
+                            // The penultimate state when the writer has had
+                            // something written.
+                            *this.state = State::PollComplete(true);
+                        },
+                    }
+                },
+                State::PollComplete(last) => {
+                    let out = ready!(this.writer.as_mut().poll_complete(cx));
+                    this.trigger.on_complete();
+                    // Upstream stopped producing in the last iteration, or the
+                    // writer now indicates that it cannot be polled anymore, so
+                    // set the state to `Terminated` to end on the next poll
+                    // after returning the last item.
+                    if last || this.writer.is_terminated() {
+                        *this.state = State::Terminated;
+                    } else {
+                        // Otherwise, we can just keep polling upstream to start
+                        // building a new writer output.
+                        *this.empty = true;
+                        *this.state = State::PollNext;
+                    }
+                    return Poll::Ready(Some(out));
+                },
+                State::Terminated => {
+                    *this.is_terminated = true;
+                    return Poll::Ready(None);
+                },
+            }
+        }
+    }
+}
+
+impl<St, Wr, T> Debug for CompleteLoop<St, Wr, T>
+where
+    St: Stream + Debug,
+    St::Item: Debug,
+    Wr: Debug,
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompleteLoop")
+            .field("stream", &self.stream)
+            .field("writer", &self.writer)
+            .field("buffered", &self.buffered)
+            .field("trigger", &self.trigger)
+            .field("state", &self.state)
+            .field("empty", &self.empty)
+            .field("is_terminated", &self.is_terminated)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    PollNext,
+    PollComplete(bool),
+    Terminated,
+}
@@ -0,0 +1,93 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::stream::{FusedStream, Stream};
+use tokio::time::{Instant, Sleep};
+
+use super::complete_loop::{CompleteLoop, Trigger};
+use crate::FusedMultipartWrite;
+
+pin_project_lite::pin_project! {
+    /// Stream for [`complete_on_idle`].
+    ///
+    /// [`complete_on_idle`]: super::MultipartStreamExt::complete_on_idle
+    #[must_use = "futures do nothing unless polled"]
+    pub struct CompleteOnIdle<St: Stream, Wr> {
+        #[pin]
+        inner: CompleteLoop<St, Wr, Idle>,
+    }
+}
+
+impl<St: Stream, Wr> CompleteOnIdle<St, Wr> {
+    pub(super) fn new(stream: St, writer: Wr, gap: Duration) -> Self {
+        let trigger = Idle { gap, idle: None };
+        Self { inner: CompleteLoop::new(stream, writer, trigger) }
+    }
+}
+
+impl<St, Wr> FusedStream for CompleteOnIdle<St, Wr>
+where
+    St: Stream,
+    Wr: FusedMultipartWrite<St::Item>,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+impl<St, Wr> Stream for CompleteOnIdle<St, Wr>
+where
+    St: Stream,
+    Wr: FusedMultipartWrite<St::Item>,
+{
+    type Item = Result<Wr::Output, Wr::Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.project().inner.poll_next(cx)
+    }
+}
+
+impl<St, Wr> Debug for CompleteOnIdle<St, Wr>
+where
+    St: Stream + Debug,
+    St::Item: Debug,
+    Wr: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompleteOnIdle").field("inner", &self.inner).finish()
+    }
+}
+
+/// Completes the writer when `gap` has passed since the last item was
+/// written.
+#[derive(Debug)]
+struct Idle {
+    gap: Duration,
+    idle: Option<Pin<Box<Sleep>>>,
+}
+
+impl<Recv> Trigger<Recv> for Idle {
+    fn on_sent(&mut self, _: Recv) -> bool {
+        // Every item starts the gap over.
+        let deadline = Instant::now() + self.gap;
+        match self.idle.as_mut() {
+            Some(idle) => idle.as_mut().reset(deadline),
+            _ => self.idle = Some(Box::pin(tokio::time::sleep_until(deadline))),
+        }
+        false
+    }
+
+    fn poll_expired(&mut self, empty: bool, cx: &mut Context<'_>) -> bool {
+        // Polling the gap also registers it with the waker, so the writer is
+        // completed when it passes rather than when the next item arrives.
+        match self.idle.as_mut() {
+            Some(idle) if !empty => idle.as_mut().poll(cx).is_ready(),
+            _ => false,
+        }
+    }
+}
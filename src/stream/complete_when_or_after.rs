@@ -0,0 +1,117 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::stream::{FusedStream, Stream};
+use tokio::time::{Instant, Sleep};
+
+use super::complete_loop::{CompleteLoop, Trigger};
+use crate::FusedMultipartWrite;
+
+pin_project_lite::pin_project! {
+    /// Stream for [`complete_when_or_after`] and [`complete_every`].
+    ///
+    /// [`complete_when_or_after`]: super::MultipartStreamExt::complete_when_or_after
+    /// [`complete_every`]: super::MultipartStreamExt::complete_every
+    #[must_use = "futures do nothing unless polled"]
+    pub struct CompleteWhenOrAfter<St: Stream, Wr, F> {
+        #[pin]
+        inner: CompleteLoop<St, Wr, WhenOrAfter<F>>,
+    }
+}
+
+impl<St: Stream, Wr, F> CompleteWhenOrAfter<St, Wr, F> {
+    pub(super) fn new(stream: St, writer: Wr, f: F, period: Duration) -> Self {
+        let trigger = WhenOrAfter { f, period, window: None };
+        Self { inner: CompleteLoop::new(stream, writer, trigger) }
+    }
+}
+
+impl<St, Wr, F> FusedStream for CompleteWhenOrAfter<St, Wr, F>
+where
+    St: Stream,
+    Wr: FusedMultipartWrite<St::Item>,
+    F: FnMut(Wr::Recv) -> bool,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+impl<St, Wr, F> Stream for CompleteWhenOrAfter<St, Wr, F>
+where
+    St: Stream,
+    Wr: FusedMultipartWrite<St::Item>,
+    F: FnMut(Wr::Recv) -> bool,
+{
+    type Item = Result<Wr::Output, Wr::Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.project().inner.poll_next(cx)
+    }
+}
+
+impl<St, Wr, F> Debug for CompleteWhenOrAfter<St, Wr, F>
+where
+    St: Stream + Debug,
+    St::Item: Debug,
+    Wr: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompleteWhenOrAfter")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// Completes the writer when `f` returns `true`, or when a window of
+/// `period` ends with something written in it.
+struct WhenOrAfter<F> {
+    f: F,
+    period: Duration,
+    window: Option<Pin<Box<Sleep>>>,
+}
+
+impl<F: FnMut(Recv) -> bool, Recv> Trigger<Recv> for WhenOrAfter<F> {
+    fn on_sent(&mut self, ret: Recv) -> bool {
+        (self.f)(ret)
+    }
+
+    fn poll_expired(&mut self, empty: bool, cx: &mut Context<'_>) -> bool {
+        let period = self.period;
+        let window = self
+            .window
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(period)));
+        while window.as_mut().poll(cx).is_ready() {
+            window.as_mut().reset(Instant::now() + period);
+            // An empty window is skipped, and otherwise the window ends now.
+            // Either way the new deadline is polled to register it with the
+            // waker.
+            if !empty {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn on_complete(&mut self) {
+        // The next window starts now, whether this one ended on time or
+        // because of `f`.
+        if let Some(window) = self.window.as_mut() {
+            window.as_mut().reset(Instant::now() + self.period);
+        }
+    }
+}
+
+impl<F> Debug for WhenOrAfter<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WhenOrAfter")
+            .field("period", &self.period)
+            .field("window", &self.window)
+            .finish()
+    }
+}
@@ -6,52 +6,64 @@ use futures_core::future::{FusedFuture, Future};
 use futures_core::ready;
 use futures_core::stream::Stream;
 
-use crate::MultipartWrite;
+use crate::write::{PollAbort, poll_abort};
+use crate::{AbortMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// Future for [`complete_with`].
     ///
     /// [`complete_with`]: super::MultipartStreamExt::complete_with
+    ///
+    /// `E` is the error of the writer, which is kept while the writer is
+    /// aborted with `abort_on_error`.  It is a separate parameter so that the
+    /// struct does not need a `MultipartWrite` bound on the writer, and it
+    /// always has its default.  Code that names this type with fewer
+    /// parameters still compiles, but a parameter was added.
     #[must_use = "futures do nothing unless polled"]
-    pub struct CompleteWith<St: Stream, Wr> {
+    pub struct CompleteWith<
+        St: Stream,
+        Wr,
+        E = <Wr as MultipartWrite<<St as Stream>::Item>>::Error,
+    > {
         #[pin]
         writer: Wr,
         #[pin]
         stream: Option<St>,
         buffered: Option<St::Item>,
+        abort: Option<PollAbort<Wr>>,
+        error: Option<E>,
         is_terminated: bool,
     }
 }
 
-impl<St: Stream, Wr> CompleteWith<St, Wr> {
+impl<St: Stream, Wr: MultipartWrite<St::Item>> CompleteWith<St, Wr> {
     pub(super) fn new(stream: St, writer: Wr) -> Self {
         Self {
             writer,
             stream: Some(stream),
             buffered: None,
+            abort: None,
+            error: None,
             is_terminated: false,
         }
     }
-}
 
-impl<St, Wr> FusedFuture for CompleteWith<St, Wr>
-where
-    St: Stream,
-    Wr: MultipartWrite<St::Item>,
-{
-    fn is_terminated(&self) -> bool {
-        self.is_terminated
+    /// Abort the writer when it returns an error, before the error is
+    /// returned by the future.
+    ///
+    /// An error returned by aborting the writer is ignored in favor of the
+    /// error that caused it.
+    pub fn abort_on_error(self) -> Self
+    where
+        Wr: AbortMultipartWrite<St::Item>,
+    {
+        Self { abort: Some(poll_abort::<Wr, St::Item>), ..self }
     }
-}
 
-impl<St, Wr> Future for CompleteWith<St, Wr>
-where
-    St: Stream,
-    Wr: MultipartWrite<St::Item>,
-{
-    type Output = Result<Wr::Output, Wr::Error>;
-
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Wr::Output, Wr::Error>> {
         let mut this = self.project();
 
         loop {
@@ -68,9 +80,7 @@ where
             }
 
             let Some(st) = this.stream.as_mut().as_pin_mut() else {
-                let out = ready!(this.writer.as_mut().poll_complete(cx));
-                *this.is_terminated = true;
-                return Poll::Ready(out);
+                return this.writer.as_mut().poll_complete(cx);
             };
 
             match ready!(st.poll_next(cx)) {
@@ -81,17 +91,63 @@ where
     }
 }
 
-impl<St, Wr> Debug for CompleteWith<St, Wr>
+impl<St, Wr> FusedFuture for CompleteWith<St, Wr>
+where
+    St: Stream,
+    Wr: MultipartWrite<St::Item>,
+{
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl<St, Wr> Future for CompleteWith<St, Wr>
+where
+    St: Stream,
+    Wr: MultipartWrite<St::Item>,
+{
+    type Output = Result<Wr::Output, Wr::Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        if self.error.is_none() {
+            match ready!(self.as_mut().poll_write(cx)) {
+                Err(e) if self.abort.is_some() => {
+                    let this = self.as_mut().project();
+                    *this.buffered = None;
+                    *this.error = Some(e);
+                },
+                out => {
+                    *self.as_mut().project().is_terminated = true;
+                    return Poll::Ready(out);
+                },
+            }
+        }
+        // The error is held on to while the writer is aborted.
+        let this = self.project();
+        if let Some(abort) = this.abort {
+            ready!(abort(this.writer, cx));
+        }
+        *this.is_terminated = true;
+        Poll::Ready(Err(this.error.take().unwrap()))
+    }
+}
+
+impl<St, Wr, E> Debug for CompleteWith<St, Wr, E>
 where
     St: Stream + Debug,
     St::Item: Debug,
     Wr: Debug,
+    E: Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("CompleteWith")
             .field("writer", &self.writer)
             .field("stream", &self.stream)
             .field("buffered", &self.buffered)
+            .field("error", &self.error)
             .field("is_terminated", &self.is_terminated)
             .finish()
     }
@@ -3,8 +3,29 @@
 //! This module contains the extension [`MultipartStreamExt`] that has adapters
 //! for using a `MultipartWrite` with a stream.
 use futures_core::stream::Stream;
+#[cfg(feature = "tokio")]
+use std::time::Duration;
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{FusedMultipartWrite, MakeMultipartWrite, MultipartWrite};
+
+mod complete_each;
+pub use complete_each::CompleteEach;
+
+mod complete_loop;
+
+#[cfg(feature = "tokio")]
+mod complete_on_idle;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[doc(inline)]
+pub use complete_on_idle::CompleteOnIdle;
+
+#[cfg(feature = "tokio")]
+mod complete_when_or_after;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[doc(inline)]
+pub use complete_when_or_after::CompleteWhenOrAfter;
 
 mod complete_with;
 pub use complete_with::CompleteWith;
@@ -20,6 +41,9 @@ pub trait MultipartStreamExt: Stream {
     /// Consumes a stream by passing to the provided `MultipartWrite`, returning
     /// the complete output of the writer in a future.
     ///
+    /// If the writer can be aborted, [`CompleteWith::abort_on_error`] makes
+    /// the future abort it when it fails.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -49,6 +73,46 @@ pub trait MultipartStreamExt: Stream {
         CompleteWith::new(self, writer)
     }
 
+    /// Transforms this stream into a stream of `Result`s returned by
+    /// completing a new writer for each output.
+    ///
+    /// This is like [`try_complete_when`](Self::try_complete_when), but rather
+    /// than reusing one writer, a writer is made by `make` when the first part
+    /// of each output arrives.  It is written to until `f` evaluates to `true`
+    /// for the value returned by sending a part, or the stream ends, and then
+    /// it is completed and dropped.  The writer does not need to be usable
+    /// after it has been completed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use futures::stream::{self, TryStreamExt as _};
+    /// use multipart_write::{MultipartStreamExt as _, write};
+    ///
+    /// let make = || async {
+    ///     let init: Vec<u8> = Vec::new();
+    ///     Ok::<_, std::convert::Infallible>(write::extend(init))
+    /// };
+    ///
+    /// let output = stream::iter(1..=5)
+    ///     .complete_each(make, |()| false)
+    ///     .try_collect::<Vec<_>>()
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// assert_eq!(output, vec![vec![1, 2, 3, 4, 5]]);
+    /// # });
+    /// ```
+    fn complete_each<M, F>(self, make: M, f: F) -> CompleteEach<Self, M, F>
+    where
+        M: MakeMultipartWrite<Self::Item>,
+        F: FnMut(<M::Writer as MultipartWrite<Self::Item>>::Recv) -> bool,
+        Self: Sized,
+    {
+        CompleteEach::new(self, make, f)
+    }
+
     /// Transforms this stream into a stream of `Result`s returned by polling
     /// the writer for completion.
     ///
@@ -60,7 +124,14 @@ pub trait MultipartStreamExt: Stream {
     /// type. It must be safe to continue using the writer after it produces
     /// the next completed item for the stream, so prior to this it must check
     /// that the inner writer has not terminated. If either the stream or
-    /// the writer are terminated, the stream is ended.
+    /// the writer are terminated, the stream is ended.  This is also checked
+    /// after the writer fails to write an item, so a writer from
+    /// [`poison_on_error`] ends the stream after its first error.
+    ///
+    /// [`poison_on_error`]: crate::MultipartWriteExt::poison_on_error
+    ///
+    /// If the writer can be aborted, [`TryCompleteWhen::abort_on_error`] makes
+    /// the stream abort it when it fails and go on to the next output.
     ///
     /// # Examples
     ///
@@ -111,4 +182,143 @@ pub trait MultipartStreamExt: Stream {
     {
         TryCompleteWhen::new(self, writer, f)
     }
+
+    /// Transforms this stream into a stream of `Result`s returned by
+    /// completing the writer whenever a window of time `period` has passed.
+    ///
+    /// This is like [`try_complete_when`](Self::try_complete_when), except
+    /// that an output is produced at least every `period` while the stream is
+    /// producing items, even if it is slow to produce them.  A window in which
+    /// nothing was written to the writer is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use std::time::Duration;
+    ///
+    /// use futures::stream::{self, TryStreamExt as _};
+    /// use multipart_write::{MultipartStreamExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let output = stream::iter(1..=5)
+    ///     .complete_every(write::extend(init), Duration::from_secs(30))
+    ///     .try_collect::<Vec<_>>()
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// assert_eq!(output, vec![vec![1, 2, 3, 4, 5]]);
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn complete_every<Wr>(
+        self,
+        writer: Wr,
+        period: Duration,
+    ) -> CompleteWhenOrAfter<Self, Wr, fn(Wr::Recv) -> bool>
+    where
+        Wr: FusedMultipartWrite<Self::Item>,
+        Self: Sized,
+    {
+        CompleteWhenOrAfter::new(self, writer, |_| false, period)
+    }
+
+    /// Transforms this stream into a stream of `Result`s returned by
+    /// completing the writer when the given closure evaluates to `true`, or
+    /// when a window of time `period` has passed, whichever is first.
+    ///
+    /// This combines [`try_complete_when`](Self::try_complete_when) and
+    /// [`complete_every`](Self::complete_every).  A new window starts every
+    /// time an output is produced, for either reason.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use std::time::Duration;
+    ///
+    /// use futures::stream::{self, TryStreamExt as _};
+    /// use multipart_write::{MultipartStreamExt as _, write};
+    ///
+    /// // Complete every second part, or after 30 seconds.
+    /// let mut sent = 0;
+    /// let init: Vec<u8> = Vec::new();
+    /// let output = stream::iter(1..=5)
+    ///     .complete_when_or_after(
+    ///         write::extend(init),
+    ///         |()| {
+    ///             sent += 1;
+    ///             sent % 2 == 0
+    ///         },
+    ///         Duration::from_secs(30),
+    ///     )
+    ///     .try_collect::<Vec<_>>()
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// assert_eq!(output, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn complete_when_or_after<Wr, F>(
+        self,
+        writer: Wr,
+        f: F,
+        period: Duration,
+    ) -> CompleteWhenOrAfter<Self, Wr, F>
+    where
+        Wr: FusedMultipartWrite<Self::Item>,
+        F: FnMut(Wr::Recv) -> bool,
+        Self: Sized,
+    {
+        CompleteWhenOrAfter::new(self, writer, f, period)
+    }
+
+    /// Transforms this stream into a stream of `Result`s returned by
+    /// completing the writer whenever no item has arrived for `gap`.
+    ///
+    /// The gap starts over every time an item is written, and the writer is
+    /// completed as soon as the gap has passed, even if the stream is still
+    /// waiting for its next item.  Nothing is produced while the writer has
+    /// not had anything written to it.
+    ///
+    /// The gap is only between items, so a stream that produces an item at
+    /// least every `gap` never completes the writer on idle, however long it
+    /// goes on for.  [`complete_every`](Self::complete_every) bounds the time
+    /// between outputs instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use std::time::Duration;
+    ///
+    /// use futures::stream::{self, TryStreamExt as _};
+    /// use multipart_write::{MultipartStreamExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let output = stream::iter(1..=5)
+    ///     .complete_on_idle(write::extend(init), Duration::from_secs(30))
+    ///     .try_collect::<Vec<_>>()
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// assert_eq!(output, vec![vec![1, 2, 3, 4, 5]]);
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn complete_on_idle<Wr>(
+        self,
+        writer: Wr,
+        gap: Duration,
+    ) -> CompleteOnIdle<Self, Wr>
+    where
+        Wr: FusedMultipartWrite<Self::Item>,
+        Self: Sized,
+    {
+        CompleteOnIdle::new(self, writer, gap)
+    }
 }
@@ -5,38 +5,59 @@ use std::task::{Context, Poll};
 use futures_core::ready;
 use futures_core::stream::{FusedStream, Stream};
 
-use crate::FusedMultipartWrite;
+use super::complete_loop::{CompleteLoop, When};
+use crate::write::{PollAbort, poll_abort};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// Stream for [`try_complete_when`].
     ///
     /// [`try_complete_when`]: super::MultipartStreamExt::try_complete_when
+    ///
+    /// `E` is the error of the writer, which is kept while the writer is
+    /// aborted with `abort_on_error`.  It is a separate parameter so that the
+    /// struct does not need a `MultipartWrite` bound on the writer, and it
+    /// always has its default.  Code that names this type with fewer
+    /// parameters still compiles, but a parameter was added.
     #[must_use = "futures do nothing unless polled"]
-    pub struct TryCompleteWhen<St: Stream, Wr, F> {
-        #[pin]
-        stream: St,
+    pub struct TryCompleteWhen<
+        St: Stream,
+        Wr,
+        F,
+        E = <Wr as MultipartWrite<<St as Stream>::Item>>::Error,
+    > {
         #[pin]
-        writer: Wr,
-        buffered: Option<St::Item>,
-        f: F,
-        state: State,
-        empty: bool,
-        is_terminated: bool,
+        inner: CompleteLoop<St, Wr, When<F>>,
+        abort: Option<PollAbort<Wr>>,
+        error: Option<E>,
     }
 }
 
-impl<St: Stream, Wr, F> TryCompleteWhen<St, Wr, F> {
+impl<St, Wr, F> TryCompleteWhen<St, Wr, F>
+where
+    St: Stream,
+    Wr: MultipartWrite<St::Item>,
+{
     pub(super) fn new(stream: St, writer: Wr, f: F) -> Self {
         Self {
-            stream,
-            writer,
-            buffered: None,
-            f,
-            state: State::PollNext,
-            empty: true,
-            is_terminated: false,
+            inner: CompleteLoop::new(stream, writer, When(f)),
+            abort: None,
+            error: None,
         }
     }
+
+    /// Abort the writer when it returns an error, before the error is
+    /// returned by the stream.
+    ///
+    /// This discards the output that the writer was building, and the next
+    /// item of the stream starts a new one.  An error returned by aborting
+    /// the writer is ignored in favor of the error that caused it.
+    pub fn abort_on_error(self) -> Self
+    where
+        Wr: AbortMultipartWrite<St::Item>,
+    {
+        Self { abort: Some(poll_abort::<Wr, St::Item>), ..self }
+    }
 }
 
 impl<St, Wr, F> FusedStream for TryCompleteWhen<St, Wr, F>
@@ -46,7 +67,7 @@ where
     F: FnMut(Wr::Recv) -> bool,
 {
     fn is_terminated(&self) -> bool {
-        self.is_terminated
+        self.inner.is_terminated()
     }
 }
 
@@ -59,99 +80,40 @@ where
     type Item = Result<Wr::Output, Wr::Error>;
 
     fn poll_next(
-        self: Pin<&mut Self>,
+        mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        let mut this = self.project();
-
         loop {
-            // Try to send anything in the buffer first.
-            if this.buffered.is_some() {
-                match this.writer.as_mut().poll_ready(cx)? {
-                    Poll::Pending => return Poll::Pending,
-                    Poll::Ready(()) => {
-                        let it = this.buffered.take().unwrap();
-                        let ret = this.writer.as_mut().start_send(it)?;
-                        *this.empty = false;
-                        // Check if we should complete according to `F`.
-                        if (this.f)(ret) {
-                            // `poll_complete` not followed by stream shutdown:
-                            // the state is `PollComplete(true)` only when the
-                            // stream stopped producing but we have to do one
-                            // final call to `poll_complete` because it has had
-                            // something written to it
-                            *this.state = State::PollComplete(false);
-                        } else {
-                            *this.state = State::PollNext;
-                        }
-                    },
-                }
+            // An error is held on to while the writer is aborted.
+            if let Some(abort) = self.abort.filter(|_| self.error.is_some()) {
+                let mut this = self.as_mut().project();
+                ready!(abort(this.inner.as_mut().writer_pin_mut(), cx));
+                this.inner.discard();
+                return Poll::Ready(this.error.take().map(Err));
             }
-
-            match *this.state {
-                State::PollNext => {
-                    match ready!(this.stream.as_mut().poll_next(cx)) {
-                        Some(it) => *this.buffered = Some(it),
-                        _ => {
-                            // No more stream and nothing written to this writer
-                            // means it's over.
-                            if *this.empty {
-                                *this.is_terminated = true;
-                                return Poll::Ready(None);
-                            }
-                            // The penultimate state when the writer has had
-                            // something written.
-                            *this.state = State::PollComplete(true);
-                        },
-                    }
-                },
-                State::PollComplete(last) => {
-                    let out = ready!(this.writer.as_mut().poll_complete(cx));
-                    // Upstream stopped producing in the last iteration, or the
-                    // writer now indicates that it cannot be polled anymore, so
-                    // set the state to `Terminated` to end on the next poll
-                    // after returning the last item.
-                    if last || this.writer.is_terminated() {
-                        *this.state = State::Terminated;
-                    } else {
-                        // Otherwise, we can just keep polling upstream to start
-                        // building a new writer utput.
-                        *this.empty = true;
-                        *this.state = State::PollNext;
-                    }
-                    return Poll::Ready(Some(out));
-                },
-                State::Terminated => {
-                    *this.is_terminated = true;
-                    return Poll::Ready(None);
+            match ready!(self.as_mut().project().inner.poll_next(cx)) {
+                Some(Err(e)) if self.abort.is_some() => {
+                    let this = self.as_mut().project();
+                    this.inner.discard();
+                    *this.error = Some(e);
                 },
+                res => return Poll::Ready(res),
             }
         }
     }
 }
 
-impl<St, Wr, F> Debug for TryCompleteWhen<St, Wr, F>
+impl<St, Wr, F, E> Debug for TryCompleteWhen<St, Wr, F, E>
 where
     St: Stream + Debug,
     St::Item: Debug,
-    St: Debug,
     Wr: Debug,
+    E: Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("TryCompleteWhen")
-            .field("stream", &self.stream)
-            .field("writer", &self.writer)
-            .field("buffered", &self.buffered)
-            .field("state", &self.state)
-            .field("empty", &self.empty)
-            .field("is_terminated", &self.is_terminated)
+            .field("inner", &self.inner)
+            .field("error", &self.error)
             .finish()
     }
 }
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum State {
-    PollNext,
-    PollComplete(bool),
-    Terminated,
-}
@@ -0,0 +1,72 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::future::{FusedFuture, Future};
+use futures_core::ready;
+
+use crate::AbortMultipartWrite;
+
+/// Aborts a writer for `abort_on_error` and `abort_on_switch`, ignoring the
+/// result.
+pub(crate) type PollAbort<Wr> = fn(Pin<&mut Wr>, &mut Context<'_>) -> Poll<()>;
+
+pub(crate) fn poll_abort<Wr, Part>(
+    writer: Pin<&mut Wr>,
+    cx: &mut Context<'_>,
+) -> Poll<()>
+where
+    Wr: AbortMultipartWrite<Part>,
+{
+    writer.poll_abort(cx).map(|_| ())
+}
+
+/// Future for [`abort`](super::MultipartWriteExt::abort).
+#[must_use = "futures do nothing unless polled"]
+pub struct Abort<'a, Wr: ?Sized, Part> {
+    writer: &'a mut Wr,
+    is_terminated: bool,
+    _p: std::marker::PhantomData<Part>,
+}
+
+impl<Wr: ?Sized + Unpin, Part> Unpin for Abort<'_, Wr, Part> {}
+
+impl<'a, Wr: AbortMultipartWrite<Part> + ?Sized + Unpin, Part>
+    Abort<'a, Wr, Part>
+{
+    pub(super) fn new(writer: &'a mut Wr) -> Self {
+        Self { writer, is_terminated: false, _p: std::marker::PhantomData }
+    }
+}
+
+impl<Wr: ?Sized + AbortMultipartWrite<Part> + Unpin, Part> FusedFuture
+    for Abort<'_, Wr, Part>
+{
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl<Wr: ?Sized + AbortMultipartWrite<Part> + Unpin, Part> Future
+    for Abort<'_, Wr, Part>
+{
+    type Output = Result<(), Wr::Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        let res = ready!(Pin::new(&mut self.writer).poll_abort(cx));
+        self.is_terminated = true;
+        Poll::Ready(res)
+    }
+}
+
+impl<Wr: Debug, Part> Debug for Abort<'_, Wr, Part> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Abort")
+            .field("writer", &self.writer)
+            .field("is_terminated", &self.is_terminated)
+            .finish()
+    }
+}
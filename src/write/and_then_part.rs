@@ -0,0 +1,192 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`and_then_part`].
+    ///
+    /// [`and_then_part`]: super::MultipartWriteExt::and_then_part
+    #[must_use = "futures do nothing unless polled"]
+    pub struct AndThenPart<Wr: MultipartWrite<Part>, Part, P, Fut, F> {
+        #[pin]
+        writer: Wr,
+        f: F,
+        #[pin]
+        future: Option<Fut>,
+        buffered: Option<Part>,
+        recv: Option<Wr::Recv>,
+        _p: PhantomData<fn(P) -> Part>,
+    }
+}
+
+impl<Wr: MultipartWrite<Part>, Part, P, Fut, F>
+    AndThenPart<Wr, Part, P, Fut, F>
+{
+    pub(super) fn new(writer: Wr, f: F) -> Self {
+        Self {
+            writer,
+            f,
+            future: None,
+            buffered: None,
+            recv: None,
+            _p: PhantomData,
+        }
+    }
+
+    /// Takes the value returned by the inner writer for the last part that
+    /// was sent to it, if it has not already been returned.
+    ///
+    /// Sending a part returns the value for the part before it, so this is
+    /// how to get the value for the last part after the writer has been
+    /// flushed or completed.
+    pub fn take_recv(self: Pin<&mut Self>) -> Option<Wr::Recv> {
+        self.project().recv.take()
+    }
+
+    /// Consumes `AndThenPart`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Result<(), Wr::Error>>
+    where
+        F: FnMut(P) -> Fut,
+        Fut: Future<Output = Result<Part, Wr::Error>>,
+    {
+        let mut this = self.project();
+
+        if let Some(fut) = this.future.as_mut().as_pin_mut() {
+            let res = ready!(fut.poll(cx));
+            // The future is finished even if the part could not be made.
+            this.future.set(None);
+            *this.buffered = Some(res?);
+        }
+        if this.buffered.is_some() {
+            ready!(this.writer.as_mut().poll_ready(cx))?;
+            let part = this.buffered.take().unwrap();
+            *this.recv = Some(this.writer.start_send(part)?);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Wr, Part, P, Fut, F> FusedMultipartWrite<P>
+    for AndThenPart<Wr, Part, P, Fut, F>
+where
+    Wr: FusedMultipartWrite<Part>,
+    F: FnMut(P) -> Fut,
+    Fut: Future<Output = Result<Part, Wr::Error>>,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr, Part, P, Fut, F> MultipartWrite<P> for AndThenPart<Wr, Part, P, Fut, F>
+where
+    Wr: MultipartWrite<Part>,
+    F: FnMut(P) -> Fut,
+    Fut: Future<Output = Result<Part, Wr::Error>>,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Option<Wr::Recv>;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll(cx))?;
+        self.project().writer.poll_ready(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: P,
+    ) -> Result<Self::Recv, Self::Error> {
+        let mut this = self.project();
+        this.future.set(Some((this.f)(part)));
+        Ok(this.recv.take())
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll(cx))?;
+        self.project().writer.poll_flush(cx)
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        ready!(self.as_mut().poll(cx))?;
+        self.project().writer.poll_complete(cx)
+    }
+}
+
+impl<Wr, Part, P, Fut, F> AbortMultipartWrite<P>
+    for AndThenPart<Wr, Part, P, Fut, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(P) -> Fut,
+    Fut: Future<Output = Result<Part, Wr::Error>>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+        this.future.set(None);
+        *this.buffered = None;
+        let res = ready!(this.writer.poll_abort(cx));
+        *this.recv = None;
+        Poll::Ready(res)
+    }
+}
+
+impl<Wr, Part, P, Fut, F> Debug for AndThenPart<Wr, Part, P, Fut, F>
+where
+    Wr: MultipartWrite<Part> + Debug,
+    Wr::Recv: Debug,
+    Fut: Debug,
+    Part: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AndThenPart")
+            .field("writer", &self.writer)
+            .field("future", &self.future)
+            .field("buffered", &self.buffered)
+            .field("recv", &self.recv)
+            .finish()
+    }
+}
@@ -5,7 +5,7 @@ use std::task::{Context, Poll};
 
 use futures_core::ready;
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`buffered`](super::MultipartWriteExt::buffered).
@@ -142,6 +142,20 @@ impl<Part, Wr: MultipartWrite<Part>> MultipartWrite<Part>
     }
 }
 
+impl<Part, Wr: AbortMultipartWrite<Part>> AbortMultipartWrite<Part>
+    for Buffered<Wr, Part>
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        this.buf.clear();
+        this.recv.clear();
+        this.writer.poll_abort(cx)
+    }
+}
+
 impl<Wr, Part> Debug for Buffered<Wr, Part>
 where
     Part: Debug,
@@ -0,0 +1,207 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// Returns a `MultipartWrite` that collects parts into batches of `size` and
+/// sends each batch to a writer of `Vec<Part>`.
+///
+/// The value returned by sending a part is `Some` when the part fills a batch,
+/// in which case it is the value that the inner writer returned for the batch.
+/// A partial batch is sent when the writer is flushed or completed, and the
+/// value returned for it by flushing can be taken with
+/// [`Chunks::take_recv`].
+///
+/// # Panics
+///
+/// Panics if `size` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use multipart_write::{MultipartWriteExt as _, write};
+///
+/// let batches: Vec<Vec<u8>> = Vec::new();
+/// let mut writer = write::chunks(2, batches);
+///
+/// let r1 = writer.feed(1).await.unwrap();
+/// let r2 = writer.feed(2).await.unwrap();
+/// writer.feed(3).await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!((r1, r2), (None, Some(())));
+/// assert_eq!(out, vec![vec![1, 2], vec![3]]);
+/// # })
+/// ```
+pub fn chunks<Wr, Part>(size: usize, writer: Wr) -> Chunks<Wr, Part>
+where
+    Wr: MultipartWrite<Vec<Part>>,
+{
+    Chunks::new(size, writer)
+}
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`chunks`].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Chunks<Wr: MultipartWrite<Vec<Part>>, Part> {
+        #[pin]
+        writer: Wr,
+        size: usize,
+        buf: Vec<Part>,
+        recv: Option<Wr::Recv>,
+    }
+}
+
+impl<Wr: MultipartWrite<Vec<Part>>, Part> Chunks<Wr, Part> {
+    fn new(size: usize, writer: Wr) -> Self {
+        assert!(size > 0, "chunk size must be non-zero");
+        Self { writer, size, buf: Vec::with_capacity(size), recv: None }
+    }
+
+    /// Returns the parts in the current batch that have not been sent yet.
+    pub fn pending(&self) -> &[Part] {
+        &self.buf
+    }
+
+    /// Takes the value returned by the inner writer for the partial batch
+    /// that was sent when the writer was last flushed.
+    pub fn take_recv(self: Pin<&mut Self>) -> Option<Wr::Recv> {
+        self.project().recv.take()
+    }
+
+    /// Consumes `Chunks`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Send the partial batch, if there is one.
+    fn poll_send_partial(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Wr::Error>> {
+        let mut this = self.project();
+        if this.buf.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
+        ready!(this.writer.as_mut().poll_ready(cx))?;
+        let batch = std::mem::replace(this.buf, Vec::with_capacity(*this.size));
+        *this.recv = Some(this.writer.start_send(batch)?);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Wr, Part> FusedMultipartWrite<Part> for Chunks<Wr, Part>
+where
+    Wr: FusedMultipartWrite<Vec<Part>>,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr, Part> MultipartWrite<Part> for Chunks<Wr, Part>
+where
+    Wr: MultipartWrite<Vec<Part>>,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Option<Wr::Recv>;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // The next part fills the batch, so the inner writer has to be ready
+        // to be sent the batch in `start_send`.
+        if self.buf.len() + 1 >= self.size {
+            return self.project().writer.poll_ready(cx);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        this.buf.push(part);
+        if this.buf.len() < *this.size {
+            return Ok(None);
+        }
+        let batch = std::mem::replace(this.buf, Vec::with_capacity(*this.size));
+        this.writer.start_send(batch).map(Some)
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_send_partial(cx))?;
+        self.project().writer.poll_flush(cx)
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        ready!(self.as_mut().poll_send_partial(cx))?;
+        let this = self.project();
+        let out = ready!(this.writer.poll_complete(cx));
+        *this.recv = None;
+        Poll::Ready(out)
+    }
+}
+
+impl<Wr, Part> AbortMultipartWrite<Part> for Chunks<Wr, Part>
+where
+    Wr: AbortMultipartWrite<Vec<Part>>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        this.buf.clear();
+        *this.recv = None;
+        this.writer.poll_abort(cx)
+    }
+}
+
+impl<Wr, Part> Debug for Chunks<Wr, Part>
+where
+    Part: Debug,
+    Wr: Debug + MultipartWrite<Vec<Part>>,
+    Wr::Recv: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Chunks")
+            .field("writer", &self.writer)
+            .field("size", &self.size)
+            .field("buf", &self.buf)
+            .field("recv", &self.recv)
+            .finish()
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::ready;
+use tokio::time::{Instant, Sleep};
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// When the circuit of a writer returned by
+/// [`circuit_breaker`](super::MultipartWriteExt::circuit_breaker) opens, and
+/// what happens while it is open.
+///
+/// The circuit opens when the rate of errors among the last `window` results
+/// of `poll_ready`, `start_send` and `poll_flush` reaches `failure_rate`, as
+/// long as there are at least `min_calls` of them.  A `min_calls` greater
+/// than `window` is treated as `window`.  After it has been open for
+/// `open_for`, the circuit is half-open and the writer is tried again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircuitBreakerConfig {
+    failure_rate: f64,
+    min_calls: usize,
+    window: usize,
+    open_for: Duration,
+    fail_fast: bool,
+}
+
+impl CircuitBreakerConfig {
+    /// Create a new configuration that opens the circuit when half of the last
+    /// 20 results were errors, and keeps it open for 30 seconds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the rate of errors at which the circuit opens.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not greater than zero and at most one.
+    pub fn failure_rate(self, rate: f64) -> Self {
+        assert!(
+            rate > 0.0 && rate <= 1.0,
+            "failure rate must be in the range (0, 1]"
+        );
+        Self { failure_rate: rate, ..self }
+    }
+
+    /// Set the number of results needed before the circuit can open.
+    ///
+    /// Only the last `window` results are kept, so a `min_calls` greater than
+    /// the window is treated as the size of the window.
+    pub fn min_calls(self, min_calls: usize) -> Self {
+        Self { min_calls, ..self }
+    }
+
+    /// Set the number of most recent results the error rate is measured over.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero.
+    pub fn window(self, window: usize) -> Self {
+        assert!(window > 0, "window must be non-zero");
+        Self { window, ..self }
+    }
+
+    /// Set how long the circuit stays open before the writer is tried again.
+    pub fn open_for(self, open_for: Duration) -> Self {
+        Self { open_for, ..self }
+    }
+
+    /// Make `poll_ready` wait until the writer can be tried again while the
+    /// circuit is open, instead of failing with [`CircuitOpen`].
+    pub fn wait_while_open(self) -> Self {
+        Self { fail_fast: false, ..self }
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_rate: 0.5,
+            min_calls: 20,
+            window: 20,
+            open_for: Duration::from_secs(30),
+            fail_fast: true,
+        }
+    }
+}
+
+/// The state of the circuit of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CircuitState {
+    /// The writer is used as normal and its results are recorded.
+    Closed,
+    /// The writer has failed too often and is not being used.
+    Open,
+    /// The writer is being tried again.  The circuit closes when a part is
+    /// sent or the writer is flushed or completed, and opens again on the
+    /// first error.
+    HalfOpen,
+}
+
+/// Error for [`circuit_breaker`](super::MultipartWriteExt::circuit_breaker)
+/// returned by `poll_ready` when the circuit is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CircuitOpen {
+    retry_after: Duration,
+}
+
+impl CircuitOpen {
+    /// Returns how long until the writer can be tried again.
+    pub fn retry_after(&self) -> Duration {
+        self.retry_after
+    }
+}
+
+impl Display for CircuitOpen {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "circuit is open, retry after {:?}", self.retry_after)
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+impl From<CircuitOpen> for std::io::Error {
+    fn from(e: CircuitOpen) -> Self {
+        std::io::Error::other(e)
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for
+    /// [`circuit_breaker`](super::MultipartWriteExt::circuit_breaker).
+    #[must_use = "futures do nothing unless polled"]
+    pub struct CircuitBreaker<Wr, Part> {
+        #[pin]
+        writer: Wr,
+        config: CircuitBreakerConfig,
+        state: CircuitState,
+        results: VecDeque<bool>,
+        failures: usize,
+        probe: Option<Pin<Box<Sleep>>>,
+        _p: PhantomData<fn(Part)>,
+    }
+}
+
+impl<Wr, Part> CircuitBreaker<Wr, Part> {
+    pub(super) fn new(writer: Wr, mut config: CircuitBreakerConfig) -> Self {
+        // The circuit could never open if more results were needed than are
+        // kept.
+        config.min_calls = config.min_calls.min(config.window);
+        Self {
+            writer,
+            config,
+            state: CircuitState::Closed,
+            results: VecDeque::with_capacity(config.window),
+            failures: 0,
+            probe: None,
+            _p: PhantomData,
+        }
+    }
+
+    /// Returns the current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    /// Consumes `CircuitBreaker`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Record the result of a call to the writer, opening the circuit if
+    /// there have been too many errors.
+    fn record(self: Pin<&mut Self>, failed: bool) {
+        let this = self.project();
+        let trip = match this.state {
+            CircuitState::Closed => {
+                if this.results.len() == this.config.window
+                    && this.results.pop_front() == Some(true)
+                {
+                    *this.failures -= 1;
+                }
+                this.results.push_back(failed);
+                *this.failures += usize::from(failed);
+                let calls = this.results.len();
+                calls >= this.config.min_calls
+                    && *this.failures as f64 / calls as f64
+                        >= this.config.failure_rate
+            },
+            CircuitState::HalfOpen => failed,
+            CircuitState::Open => false,
+        };
+        if trip {
+            *this.state = CircuitState::Open;
+            this.results.clear();
+            *this.failures = 0;
+            let sleep = tokio::time::sleep(this.config.open_for);
+            *this.probe = Some(Box::pin(sleep));
+        }
+    }
+
+    /// Record the result of a call that uses the writer, which closes a
+    /// half-open circuit if it succeeded.
+    fn record_probe(mut self: Pin<&mut Self>, failed: bool) {
+        let state = self.as_mut().project().state;
+        // Something made it through the writer, so it is working again.
+        if *state == CircuitState::HalfOpen && !failed {
+            *state = CircuitState::Closed;
+            return;
+        }
+        self.record(failed);
+    }
+}
+
+impl<Wr, Part> FusedMultipartWrite<Part> for CircuitBreaker<Wr, Part>
+where
+    Wr: FusedMultipartWrite<Part>,
+    Wr::Error: From<CircuitOpen>,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr, Part> MultipartWrite<Part> for CircuitBreaker<Wr, Part>
+where
+    Wr: MultipartWrite<Part>,
+    Wr::Error: From<CircuitOpen>,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Wr::Recv;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.as_mut().project();
+        if *this.state == CircuitState::Open {
+            let probe = this.probe.as_mut().expect("open circuit has a probe");
+            if this.config.fail_fast {
+                if probe.as_mut().poll(cx).is_pending() {
+                    let now = Instant::now();
+                    let retry_after =
+                        probe.deadline().saturating_duration_since(now);
+                    return Poll::Ready(
+                        Err(CircuitOpen { retry_after }.into()),
+                    );
+                }
+            } else {
+                ready!(probe.as_mut().poll(cx));
+            }
+            *this.probe = None;
+            *this.state = CircuitState::HalfOpen;
+        }
+        let res = ready!(this.writer.poll_ready(cx));
+        self.record(res.is_err());
+        Poll::Ready(res)
+    }
+
+    fn start_send(
+        mut self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let res = self.as_mut().project().writer.start_send(part);
+        self.record_probe(res.is_err());
+        res
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let res = ready!(self.as_mut().project().writer.poll_flush(cx));
+        self.record_probe(res.is_err());
+        Poll::Ready(res)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        let this = self.project();
+        let res = ready!(this.writer.poll_complete(cx));
+        if *this.state == CircuitState::HalfOpen && res.is_ok() {
+            *this.state = CircuitState::Closed;
+        }
+        Poll::Ready(res)
+    }
+}
+
+impl<Wr, Part> AbortMultipartWrite<Part> for CircuitBreaker<Wr, Part>
+where
+    Wr: AbortMultipartWrite<Part>,
+    Wr::Error: From<CircuitOpen>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // Cleaning up is allowed even when the circuit is open.
+        self.project().writer.poll_abort(cx)
+    }
+}
+
+impl<Wr: Debug, Part> Debug for CircuitBreaker<Wr, Part> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("writer", &self.writer)
+            .field("config", &self.config)
+            .field("state", &self.state)
+            .field("results", &self.results)
+            .field("failures", &self.failures)
+            .field("probe", &self.probe)
+            .finish()
+    }
+}
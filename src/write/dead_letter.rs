@@ -0,0 +1,230 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use super::fanout::join;
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for
+    /// [`dead_letter`](super::MultipartWriteExt::dead_letter).
+    #[must_use = "futures do nothing unless polled"]
+    pub struct DeadLetter<
+        Wr: MultipartWrite<Part>,
+        D: MultipartWrite<(Part, Wr::Error)>,
+        Part,
+        F,
+    > {
+        #[pin]
+        writer: Wr,
+        #[pin]
+        dlq: D,
+        f: F,
+        letter: Option<(Part, Wr::Error)>,
+        wro: Option<Wr::Output>,
+        dlqo: Option<D::Output>,
+    }
+}
+
+impl<Wr, D, Part, F> DeadLetter<Wr, D, Part, F>
+where
+    Wr: MultipartWrite<Part>,
+    D: MultipartWrite<(Part, Wr::Error)>,
+{
+    pub(super) fn new(writer: Wr, dlq: D, f: F) -> Self {
+        Self { writer, dlq, f, letter: None, wro: None, dlqo: None }
+    }
+
+    /// Consumes `DeadLetter`, returning the underlying writer and dead letter
+    /// writer.
+    pub fn into_inner(self) -> (Wr, D) {
+        (self.writer, self.dlq)
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Acquires a reference to the dead letter writer.
+    pub fn dlq_ref(&self) -> &D {
+        &self.dlq
+    }
+
+    /// Acquires a pinned mutable reference to the dead letter writer.
+    ///
+    /// It is inadvisable to directly write to the dead letter writer.
+    pub fn dlq_pin_mut(self: Pin<&mut Self>) -> Pin<&mut D> {
+        self.project().dlq
+    }
+
+    fn poll_send_letter(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Wr::Error>>
+    where
+        Wr::Error: From<D::Error>,
+    {
+        let mut this = self.project();
+        if this.letter.is_some() {
+            ready!(this.dlq.as_mut().poll_ready(cx))?;
+            let letter = this.letter.take().unwrap();
+            let _ = this.dlq.as_mut().start_send(letter)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Wr, D, Part, F> FusedMultipartWrite<Part> for DeadLetter<Wr, D, Part, F>
+where
+    Part: Clone,
+    Wr: FusedMultipartWrite<Part>,
+    Wr::Error: From<D::Error>,
+    D: FusedMultipartWrite<(Part, Wr::Error)>,
+    F: FnMut(&Wr::Error) -> bool,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated() || self.dlq.is_terminated()
+    }
+}
+
+impl<Wr, D, Part, F> MultipartWrite<Part> for DeadLetter<Wr, D, Part, F>
+where
+    Part: Clone,
+    Wr: MultipartWrite<Part>,
+    Wr::Error: From<D::Error>,
+    D: MultipartWrite<(Part, Wr::Error)>,
+    F: FnMut(&Wr::Error) -> bool,
+{
+    type Error = Wr::Error;
+    type Output = (Wr::Output, D::Output);
+    type Recv = Option<Wr::Recv>;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_send_letter(cx))?;
+        self.project().writer.poll_ready(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        match this.writer.start_send(part.clone()) {
+            Ok(ret) => Ok(Some(ret)),
+            // The letter is sent the next time the writer is polled, when the
+            // dead letter writer is ready for it.
+            Err(e) if (this.f)(&e) => {
+                *this.letter = Some((part, e));
+                Ok(None)
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_send_letter(cx))?;
+        let this = self.project();
+        let ready1 = this.writer.poll_flush(cx);
+        let ready2 = this.dlq.poll_flush(cx).map_err(Wr::Error::from);
+        join(ready1, ready2)
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        ready!(self.as_mut().poll_send_letter(cx))?;
+        let this = self.project();
+        let res1 = match this.wro {
+            Some(_) => Poll::Ready(Ok(())),
+            None => this.writer.poll_complete(cx).map_ok(|out| {
+                *this.wro = Some(out);
+            }),
+        };
+        let res2 = match this.dlqo {
+            Some(_) => Poll::Ready(Ok(())),
+            None => this.dlq.poll_complete(cx).map(|res| match res {
+                Ok(out) => {
+                    *this.dlqo = Some(out);
+                    Ok(())
+                },
+                Err(e) => Err(Wr::Error::from(e)),
+            }),
+        };
+        if let Err(e) = ready!(join(res1, res2)) {
+            *this.wro = None;
+            *this.dlqo = None;
+            return Poll::Ready(Err(e));
+        }
+        let out = this.wro.take().unwrap();
+        let dlq_out = this.dlqo.take().unwrap();
+        Poll::Ready(Ok((out, dlq_out)))
+    }
+}
+
+impl<Wr, D, Part, F> AbortMultipartWrite<Part> for DeadLetter<Wr, D, Part, F>
+where
+    Part: Clone,
+    Wr: AbortMultipartWrite<Part>,
+    Wr::Error: From<D::Error>,
+    D: AbortMultipartWrite<(Part, Wr::Error)>,
+    F: FnMut(&Wr::Error) -> bool,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        // The dead letters belong to the write being aborted, so they are
+        // discarded along with it.
+        *this.letter = None;
+        *this.wro = None;
+        *this.dlqo = None;
+        let ready1 = this.writer.poll_abort(cx);
+        let ready2 = this.dlq.poll_abort(cx).map_err(Wr::Error::from);
+        join(ready1, ready2)
+    }
+}
+
+impl<Wr, D, Part, F> Debug for DeadLetter<Wr, D, Part, F>
+where
+    Part: Debug,
+    Wr: MultipartWrite<Part> + Debug,
+    Wr::Error: Debug,
+    Wr::Output: Debug,
+    D: MultipartWrite<(Part, Wr::Error)> + Debug,
+    D::Output: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeadLetter")
+            .field("writer", &self.writer)
+            .field("dlq", &self.dlq)
+            .field("letter", &self.letter)
+            .field("wro", &self.wro)
+            .field("dlqo", &self.dlqo)
+            .finish()
+    }
+}
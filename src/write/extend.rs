@@ -3,7 +3,7 @@ use std::fmt::{self, Debug, Formatter};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 /// Returns a value that becomes a `MultipartWrite` over any `A` where `T:
 /// std::iter::Extend<A>`.
@@ -83,6 +83,19 @@ where
     }
 }
 
+impl<A, T> AbortMultipartWrite<A> for Extend<T>
+where
+    T: Unpin + Default + std::iter::Extend<A>,
+{
+    fn poll_abort(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
 impl<T> Debug for Extend<T>
 where
     T: Debug,
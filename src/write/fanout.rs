@@ -4,7 +4,7 @@ use std::task::{Context, Poll};
 
 use futures_core::ready;
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`fanout`](super::MultipartWriteExt::fanout).
@@ -53,9 +53,11 @@ where
         cx: &mut Context<'_>,
     ) -> Poll<Result<(), Self::Error>> {
         let this = self.project();
-        let ready1 = this.wr1.poll_ready(cx)?.is_ready();
-        let ready2 = this.wr2.poll_ready(cx)?.is_ready();
-        if ready1 && ready2 { Poll::Ready(Ok(())) } else { Poll::Pending }
+        // Both branches are polled before either result is inspected, so that
+        // an error from one does not leave the other without a wakeup.
+        let ready1 = this.wr1.poll_ready(cx);
+        let ready2 = this.wr2.poll_ready(cx);
+        join(ready1, ready2)
     }
 
     fn start_send(
@@ -73,9 +75,9 @@ where
         cx: &mut Context<'_>,
     ) -> Poll<Result<(), Self::Error>> {
         let this = self.project();
-        let ready1 = this.wr1.poll_flush(cx)?.is_ready();
-        let ready2 = this.wr2.poll_flush(cx)?.is_ready();
-        if ready1 && ready2 { Poll::Ready(Ok(())) } else { Poll::Pending }
+        let ready1 = this.wr1.poll_flush(cx);
+        let ready2 = this.wr2.poll_flush(cx);
+        join(ready1, ready2)
     }
 
     fn poll_complete(
@@ -83,10 +85,60 @@ where
         cx: &mut Context<'_>,
     ) -> Poll<Result<Self::Output, Self::Error>> {
         let this = self.project();
-        let out1 = ready!(this.wr1.poll_complete(cx))?;
-        *this.wro1 = Some(out1);
-        let out2 = ready!(this.wr2.poll_complete(cx))?;
-        Poll::Ready(Ok((this.wro1.take().unwrap(), out2)))
+        // Drive both branches to completion at the same time, holding on to
+        // the output of the branch that finishes first.
+        let res1 = match this.wro1 {
+            Some(_) => Poll::Ready(Ok(())),
+            None => this.wr1.poll_complete(cx).map_ok(|out| {
+                *this.wro1 = Some(out);
+            }),
+        };
+        let res2 = match this.wro2 {
+            Some(_) => Poll::Ready(Ok(())),
+            None => this.wr2.poll_complete(cx).map_ok(|out| {
+                *this.wro2 = Some(out);
+            }),
+        };
+        // If one branch failed, the output of the other is kept for when
+        // completing is tried again.
+        ready!(join(res1, res2))?;
+        let out1 = this.wro1.take().unwrap();
+        let out2 = this.wro2.take().unwrap();
+        Poll::Ready(Ok((out1, out2)))
+    }
+}
+
+pub(super) fn join<E>(
+    res1: Poll<Result<(), E>>,
+    res2: Poll<Result<(), E>>,
+) -> Poll<Result<(), E>> {
+    match (res1, res2) {
+        (Poll::Ready(Err(e)), _) | (_, Poll::Ready(Err(e))) => {
+            Poll::Ready(Err(e))
+        },
+        (Poll::Ready(Ok(())), Poll::Ready(Ok(()))) => Poll::Ready(Ok(())),
+        _ => Poll::Pending,
+    }
+}
+
+impl<Wr1, Wr2, Part> AbortMultipartWrite<Part> for Fanout<Wr1, Wr2, Part>
+where
+    Part: Clone,
+    Wr1: AbortMultipartWrite<Part>,
+    Wr2: AbortMultipartWrite<Part, Error = Wr1::Error>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        // The output of a branch that already completed is from the write
+        // being aborted.
+        *this.wro1 = None;
+        *this.wro2 = None;
+        let ready1 = this.wr1.poll_abort(cx);
+        let ready2 = this.wr2.poll_abort(cx);
+        join(ready1, ready2)
     }
 }
 
@@ -0,0 +1,570 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::fanout::join;
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// Returns a `MultipartWrite` that clones each part and forwards it to every
+/// writer in the collection.
+///
+/// The value returned by sending a part, and the output of completing the
+/// writer, are collected in the same order as the writers.
+///
+/// If any of the writers fails, the others are still polled and the first
+/// error is returned.  When completing fails, the outputs of the writers that
+/// did complete are kept, and completing again only completes the writers
+/// that failed.  Aborting discards them.
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use multipart_write::{MultipartWriteExt as _, write};
+///
+/// let init: Vec<u8> = Vec::new();
+/// let writers = vec![write::extend(init.clone()), write::extend(init)];
+///
+/// let mut writer = write::fanout_all(writers);
+/// writer.send_flush(1).await.unwrap();
+/// writer.send_flush(2).await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!(out, vec![vec![1, 2], vec![1, 2]]);
+/// # })
+/// ```
+pub fn fanout_all<Wr, Part, I>(writers: I) -> FanoutAll<Wr, Part>
+where
+    I: IntoIterator<Item = Wr>,
+    Wr: MultipartWrite<Part>,
+    Part: Clone,
+{
+    FanoutAll::new(writers.into_iter().collect())
+}
+
+/// Returns a `MultipartWrite` that clones each part and forwards it to every
+/// writer in a tuple of up to 12 writers.
+///
+/// The writers can be of different types, but they must have the same error
+/// type.
+///
+/// If any of the writers fails, the others are still polled and the first
+/// error is returned.  When completing fails, the outputs of the writers that
+/// did complete are kept, and completing again only completes the writers
+/// that failed.  Aborting discards them.
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use std::collections::VecDeque;
+///
+/// use multipart_write::{MultipartWriteExt as _, write};
+///
+/// let wr1 = write::extend(String::new());
+/// let wr2: Vec<char> = Vec::new();
+/// let wr3: VecDeque<char> = VecDeque::new();
+///
+/// let mut writer = write::fanout_tuple((wr1, wr2, wr3));
+/// writer.send_flush('a').await.unwrap();
+/// writer.send_flush('b').await.unwrap();
+/// let (s, vs, vd) = writer.complete().await.unwrap();
+///
+/// assert_eq!(s, "ab");
+/// assert_eq!(vs, vec!['a', 'b']);
+/// assert_eq!(vd, VecDeque::from(['a', 'b']));
+/// # })
+/// ```
+pub fn fanout_tuple<T, Part>(writers: T) -> FanoutTuple<T, Part>
+where
+    T: FanoutBranches<Part>,
+{
+    FanoutTuple::new(writers)
+}
+
+/// `MultipartWrite` for [`fanout_all`].
+#[must_use = "futures do nothing unless polled"]
+pub struct FanoutAll<Wr: MultipartWrite<Part>, Part> {
+    writers: Pin<Box<[Wr]>>,
+    outputs: Vec<Option<Wr::Output>>,
+    _p: PhantomData<fn(Part)>,
+}
+
+impl<Wr: MultipartWrite<Part>, Part> FanoutAll<Wr, Part> {
+    fn new(writers: Box<[Wr]>) -> Self {
+        let outputs = writers.iter().map(|_| None).collect();
+        Self { writers: writers.into(), outputs, _p: PhantomData }
+    }
+
+    /// Returns the number of writers parts are sent to.
+    pub fn len(&self) -> usize {
+        self.writers.len()
+    }
+
+    /// Returns `true` if there are no writers to send parts to.
+    pub fn is_empty(&self) -> bool {
+        self.writers.is_empty()
+    }
+
+    /// Acquires a reference to the underlying writers.
+    pub fn get_ref(&self) -> &[Wr] {
+        &self.writers
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writers.
+    ///
+    /// It is inadvisable to directly write to the underlying writers.
+    pub fn get_pin_mut(&mut self) -> Pin<&mut [Wr]> {
+        self.writers.as_mut()
+    }
+}
+
+// The writers are pinned on the heap and the outputs are never pinned.
+impl<Wr: MultipartWrite<Part>, Part> Unpin for FanoutAll<Wr, Part> {}
+
+pub(super) fn iter_pin_mut<T>(
+    slice: Pin<&mut [T]>,
+) -> impl DoubleEndedIterator<Item = Pin<&mut T>> {
+    // SAFETY: The elements of a pinned slice are never moved out of it, so it
+    // is safe to pin each of them in turn.
+    unsafe { slice.get_unchecked_mut() }
+        .iter_mut()
+        .map(|t| unsafe { Pin::new_unchecked(t) })
+}
+
+impl<Wr, Part> FusedMultipartWrite<Part> for FanoutAll<Wr, Part>
+where
+    Part: Clone,
+    Wr: FusedMultipartWrite<Part>,
+{
+    fn is_terminated(&self) -> bool {
+        self.writers.iter().any(|wr| wr.is_terminated())
+    }
+}
+
+impl<Wr, Part> MultipartWrite<Part> for FanoutAll<Wr, Part>
+where
+    Part: Clone,
+    Wr: MultipartWrite<Part>,
+{
+    type Error = Wr::Error;
+    type Output = Vec<Wr::Output>;
+    type Recv = Vec<Wr::Recv>;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        let mut res = Poll::Ready(Ok(()));
+        for wr in iter_pin_mut(this.writers.as_mut()) {
+            res = join(res, wr.poll_ready(cx));
+        }
+        res
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.get_mut();
+        let mut recv = Vec::with_capacity(this.writers.len());
+        let mut writers = iter_pin_mut(this.writers.as_mut());
+        // The last writer can have the part itself rather than a clone.
+        let last = writers.next_back();
+        for wr in writers {
+            recv.push(wr.start_send(part.clone())?);
+        }
+        if let Some(wr) = last {
+            recv.push(wr.start_send(part)?);
+        }
+        Ok(recv)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        let mut res = Poll::Ready(Ok(()));
+        for wr in iter_pin_mut(this.writers.as_mut()) {
+            res = join(res, wr.poll_flush(cx));
+        }
+        res
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        let this = self.get_mut();
+        let mut is_ready = true;
+        let mut error = None;
+        let writers = iter_pin_mut(this.writers.as_mut());
+        for (wr, out) in writers.zip(this.outputs.iter_mut()) {
+            if out.is_some() {
+                continue;
+            }
+            match wr.poll_complete(cx) {
+                Poll::Ready(Ok(v)) => *out = Some(v),
+                Poll::Ready(Err(e)) => {
+                    error.get_or_insert(e);
+                },
+                Poll::Pending => is_ready = false,
+            }
+        }
+        // The outputs of the writers that did complete are kept for when
+        // completing is tried again.
+        if let Some(e) = error {
+            return Poll::Ready(Err(e));
+        }
+        if !is_ready {
+            return Poll::Pending;
+        }
+        let outputs = this.outputs.iter_mut().map(|out| out.take().unwrap());
+        Poll::Ready(Ok(outputs.collect()))
+    }
+}
+
+impl<Wr, Part> AbortMultipartWrite<Part> for FanoutAll<Wr, Part>
+where
+    Part: Clone,
+    Wr: AbortMultipartWrite<Part>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        this.outputs.iter_mut().for_each(|out| *out = None);
+        let mut res = Poll::Ready(Ok(()));
+        for wr in iter_pin_mut(this.writers.as_mut()) {
+            res = join(res, wr.poll_abort(cx));
+        }
+        res
+    }
+}
+
+impl<Wr, Part> Debug for FanoutAll<Wr, Part>
+where
+    Wr: MultipartWrite<Part> + Debug,
+    Wr::Output: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FanoutAll")
+            .field("writers", &self.writers)
+            .field("outputs", &self.outputs)
+            .finish()
+    }
+}
+
+/// A tuple of writers that a part can be sent to with [`fanout_tuple`].
+///
+/// This is implemented for tuples of up to 12 writers that have the same
+/// error type.
+pub trait FanoutBranches<Part> {
+    /// The tuple of values returned by sending a part to each writer.
+    type Recv;
+
+    /// The tuple of outputs of each writer.
+    type Output;
+
+    /// The error type shared by each writer.
+    type Error;
+
+    #[doc(hidden)]
+    type Outputs: Default;
+
+    #[doc(hidden)]
+    fn poll_ready_all(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>>;
+
+    #[doc(hidden)]
+    fn start_send_all(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error>;
+
+    #[doc(hidden)]
+    fn poll_flush_all(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>>;
+
+    #[doc(hidden)]
+    fn poll_complete_all(
+        self: Pin<&mut Self>,
+        outputs: &mut Self::Outputs,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>>;
+}
+
+/// A tuple of writers that can all be aborted, which makes the writer
+/// returned by [`fanout_tuple`] an [`AbortMultipartWrite`].
+///
+/// This is implemented for tuples of up to 12 writers that implement
+/// `AbortMultipartWrite` and have the same error type.
+pub trait AbortBranches<Part>: FanoutBranches<Part> {
+    #[doc(hidden)]
+    fn poll_abort_all(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>>;
+}
+
+/// A tuple of writers that can all be terminated, which makes the writer
+/// returned by [`fanout_tuple`] a [`FusedMultipartWrite`].
+///
+/// This is implemented for tuples of up to 12 writers that implement
+/// `FusedMultipartWrite` and have the same error type.
+pub trait FusedBranches<Part>: FanoutBranches<Part> {
+    #[doc(hidden)]
+    fn is_terminated_any(&self) -> bool;
+}
+
+macro_rules! fanout_branches {
+    ($($Wr:ident $n:tt),+) => {
+        impl<Part, E, $($Wr),+> FanoutBranches<Part> for ($($Wr,)+)
+        where
+            Part: Clone,
+            $($Wr: MultipartWrite<Part, Error = E>,)+
+        {
+            type Error = E;
+            type Output = ($($Wr::Output,)+);
+            type Outputs = ($(Option<$Wr::Output>,)+);
+            type Recv = ($($Wr::Recv,)+);
+
+            fn poll_ready_all(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<(), Self::Error>> {
+                // SAFETY: The fields of the tuple are structurally pinned; they
+                // are never moved out of the pinned tuple.
+                let this = unsafe { self.get_unchecked_mut() };
+                let mut res = Poll::Ready(Ok(()));
+                $(
+                    let wr = unsafe { Pin::new_unchecked(&mut this.$n) };
+                    res = join(res, wr.poll_ready(cx));
+                )+
+                res
+            }
+
+            fn start_send_all(
+                self: Pin<&mut Self>,
+                part: Part,
+            ) -> Result<Self::Recv, Self::Error> {
+                // SAFETY: See `poll_ready_all`.
+                let this = unsafe { self.get_unchecked_mut() };
+                Ok(($(
+                    unsafe { Pin::new_unchecked(&mut this.$n) }
+                        .start_send(part.clone())?,
+                )+))
+            }
+
+            fn poll_flush_all(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<(), Self::Error>> {
+                // SAFETY: See `poll_ready_all`.
+                let this = unsafe { self.get_unchecked_mut() };
+                let mut res = Poll::Ready(Ok(()));
+                $(
+                    let wr = unsafe { Pin::new_unchecked(&mut this.$n) };
+                    res = join(res, wr.poll_flush(cx));
+                )+
+                res
+            }
+
+            fn poll_complete_all(
+                self: Pin<&mut Self>,
+                outputs: &mut Self::Outputs,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<Self::Output, Self::Error>> {
+                // SAFETY: See `poll_ready_all`.
+                let this = unsafe { self.get_unchecked_mut() };
+                let mut is_ready = true;
+                let mut error = None;
+                $(
+                    if outputs.$n.is_none() {
+                        let wr = unsafe { Pin::new_unchecked(&mut this.$n) };
+                        match wr.poll_complete(cx) {
+                            Poll::Ready(Ok(v)) => outputs.$n = Some(v),
+                            Poll::Ready(Err(e)) => {
+                                error.get_or_insert(e);
+                            },
+                            Poll::Pending => is_ready = false,
+                        }
+                    }
+                )+
+                if let Some(e) = error {
+                    return Poll::Ready(Err(e));
+                }
+                if !is_ready {
+                    return Poll::Pending;
+                }
+                Poll::Ready(Ok(($(outputs.$n.take().unwrap(),)+)))
+            }
+        }
+
+        impl<Part, E, $($Wr),+> AbortBranches<Part> for ($($Wr,)+)
+        where
+            Part: Clone,
+            $($Wr: AbortMultipartWrite<Part, Error = E>,)+
+        {
+            fn poll_abort_all(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<(), Self::Error>> {
+                // SAFETY: See `poll_ready_all`.
+                let this = unsafe { self.get_unchecked_mut() };
+                let mut res = Poll::Ready(Ok(()));
+                $(
+                    let wr = unsafe { Pin::new_unchecked(&mut this.$n) };
+                    res = join(res, wr.poll_abort(cx));
+                )+
+                res
+            }
+        }
+
+        impl<Part, E, $($Wr),+> FusedBranches<Part> for ($($Wr,)+)
+        where
+            Part: Clone,
+            $($Wr: FusedMultipartWrite<Part, Error = E>,)+
+        {
+            fn is_terminated_any(&self) -> bool {
+                $(self.$n.is_terminated())||+
+            }
+        }
+    };
+}
+
+fanout_branches!(Wr1 0);
+fanout_branches!(Wr1 0, Wr2 1);
+fanout_branches!(Wr1 0, Wr2 1, Wr3 2);
+fanout_branches!(Wr1 0, Wr2 1, Wr3 2, Wr4 3);
+fanout_branches!(Wr1 0, Wr2 1, Wr3 2, Wr4 3, Wr5 4);
+fanout_branches!(Wr1 0, Wr2 1, Wr3 2, Wr4 3, Wr5 4, Wr6 5);
+fanout_branches!(Wr1 0, Wr2 1, Wr3 2, Wr4 3, Wr5 4, Wr6 5, Wr7 6);
+fanout_branches!(Wr1 0, Wr2 1, Wr3 2, Wr4 3, Wr5 4, Wr6 5, Wr7 6, Wr8 7);
+fanout_branches!(
+    Wr1 0, Wr2 1, Wr3 2, Wr4 3, Wr5 4, Wr6 5, Wr7 6, Wr8 7, Wr9 8
+);
+fanout_branches!(
+    Wr1 0, Wr2 1, Wr3 2, Wr4 3, Wr5 4, Wr6 5, Wr7 6, Wr8 7, Wr9 8, Wr10 9
+);
+fanout_branches!(
+    Wr1 0, Wr2 1, Wr3 2, Wr4 3, Wr5 4, Wr6 5, Wr7 6, Wr8 7, Wr9 8, Wr10 9,
+    Wr11 10
+);
+fanout_branches!(
+    Wr1 0, Wr2 1, Wr3 2, Wr4 3, Wr5 4, Wr6 5, Wr7 6, Wr8 7, Wr9 8, Wr10 9,
+    Wr11 10, Wr12 11
+);
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`fanout_tuple`].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct FanoutTuple<T: FanoutBranches<Part>, Part> {
+        #[pin]
+        writers: T,
+        outputs: T::Outputs,
+        _p: PhantomData<fn(Part)>,
+    }
+}
+
+impl<T: FanoutBranches<Part>, Part> FanoutTuple<T, Part> {
+    fn new(writers: T) -> Self {
+        Self { writers, outputs: Default::default(), _p: PhantomData }
+    }
+
+    /// Consumes `FanoutTuple`, returning the underlying writers.
+    pub fn into_inner(self) -> T {
+        self.writers
+    }
+
+    /// Acquires a reference to the underlying writers.
+    pub fn get_ref(&self) -> &T {
+        &self.writers
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writers.
+    ///
+    /// It is inadvisable to directly write to the underlying writers.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.project().writers
+    }
+}
+
+impl<T: FusedBranches<Part>, Part> FusedMultipartWrite<Part>
+    for FanoutTuple<T, Part>
+{
+    fn is_terminated(&self) -> bool {
+        self.writers.is_terminated_any()
+    }
+}
+
+impl<T: FanoutBranches<Part>, Part> MultipartWrite<Part>
+    for FanoutTuple<T, Part>
+{
+    type Error = T::Error;
+    type Output = T::Output;
+    type Recv = T::Recv;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writers.poll_ready_all(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        self.project().writers.start_send_all(part)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writers.poll_flush_all(cx)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        let this = self.project();
+        this.writers.poll_complete_all(this.outputs, cx)
+    }
+}
+
+impl<T: AbortBranches<Part>, Part> AbortMultipartWrite<Part>
+    for FanoutTuple<T, Part>
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        *this.outputs = Default::default();
+        this.writers.poll_abort_all(cx)
+    }
+}
+
+impl<T, Part> Debug for FanoutTuple<T, Part>
+where
+    T: FanoutBranches<Part> + Debug,
+    T::Outputs: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FanoutTuple")
+            .field("writers", &self.writers)
+            .field("outputs", &self.outputs)
+            .finish()
+    }
+}
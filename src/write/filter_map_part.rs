@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`filter_map_part`].
@@ -100,6 +100,19 @@ where
     }
 }
 
+impl<Wr, Part, P, F> AbortMultipartWrite<P> for FilterMapPart<Wr, Part, P, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(P) -> Option<Part>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_abort(cx)
+    }
+}
+
 impl<Wr: Debug, Part, P, F> Debug for FilterMapPart<Wr, Part, P, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("FilterMapPart").field("writer", &self.writer).finish()
@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`filter_part`].
@@ -101,6 +101,19 @@ where
     }
 }
 
+impl<Wr, Part, F> AbortMultipartWrite<Part> for FilterPart<Wr, Part, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(&Part) -> bool,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_abort(cx)
+    }
+}
+
 impl<Wr: Debug, Part, F> Debug for FilterPart<Wr, Part, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("FilterPart").field("writer", &self.writer).finish()
@@ -5,7 +5,7 @@ use std::task::{Context, Poll};
 
 use futures_core::ready;
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`fold_sent`].
@@ -106,6 +106,19 @@ where
     }
 }
 
+impl<Wr, T, F, Part> AbortMultipartWrite<Part> for FoldSent<Wr, T, F, Part>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(T, &Wr::Recv) -> T,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_abort(cx)
+    }
+}
+
 impl<Wr, T, F, Part> Debug for FoldSent<Wr, T, F, Part>
 where
     Wr: Debug,
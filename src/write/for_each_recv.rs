@@ -5,7 +5,7 @@ use std::task::{Context, Poll};
 
 use futures_core::{Future, ready};
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`for_each_recv`].
@@ -114,6 +114,24 @@ where
     }
 }
 
+impl<Wr, Part, Fut, F> AbortMultipartWrite<Part>
+    for ForEachRecv<Wr, Part, Fut, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    Wr::Recv: Clone,
+    F: FnMut(Wr::Recv) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+        this.fut.set(None);
+        this.writer.poll_abort(cx)
+    }
+}
+
 impl<Wr, Part, Fut, F> Debug for ForEachRecv<Wr, Part, Fut, F>
 where
     Wr: Debug,
@@ -0,0 +1,134 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+use futures_sink::Sink;
+
+use crate::{FusedMultipartWrite, MultipartWrite};
+
+/// Returns a `MultipartWrite` that sends each part to the given [`Sink`].
+///
+/// Completing the writer closes the sink.  The output of the writer is `()`;
+/// use [`from_sink_with`] to produce an output from the sink after it has been
+/// closed.
+pub fn from_sink<Si, Part>(sink: Si) -> FromSink<Si, Part, fn(Pin<&mut Si>)>
+where
+    Si: Sink<Part>,
+{
+    FromSink::new(sink, |_| ())
+}
+
+/// [`from_sink`] but the output of the writer is the result of the closure
+/// `f`, which is called with the sink after it has been closed.
+pub fn from_sink_with<Si, Part, T, F>(sink: Si, f: F) -> FromSink<Si, Part, F>
+where
+    Si: Sink<Part>,
+    F: FnMut(Pin<&mut Si>) -> T,
+{
+    FromSink::new(sink, f)
+}
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`from_sink`] and [`from_sink_with`].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct FromSink<Si, Part, F> {
+        #[pin]
+        sink: Si,
+        f: F,
+        is_terminated: bool,
+        _p: PhantomData<fn(Part)>,
+    }
+}
+
+impl<Si, Part, F> FromSink<Si, Part, F> {
+    fn new(sink: Si, f: F) -> Self {
+        Self { sink, f, is_terminated: false, _p: PhantomData }
+    }
+
+    /// Consumes `FromSink`, returning the underlying sink.
+    pub fn into_inner(self) -> Si {
+        self.sink
+    }
+
+    /// Acquires a reference to the underlying sink.
+    pub fn get_ref(&self) -> &Si {
+        &self.sink
+    }
+
+    /// Acquires a mutable reference to the underlying sink.
+    ///
+    /// It is inadvisable to directly write to the underlying sink.
+    pub fn get_mut(&mut self) -> &mut Si {
+        &mut self.sink
+    }
+
+    /// Acquires a pinned mutable reference to the underlying sink.
+    ///
+    /// It is inadvisable to directly write to the underlying sink.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Si> {
+        self.project().sink
+    }
+}
+
+impl<Si, Part, T, F> FusedMultipartWrite<Part> for FromSink<Si, Part, F>
+where
+    Si: Sink<Part>,
+    F: FnMut(Pin<&mut Si>) -> T,
+{
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl<Si, Part, T, F> MultipartWrite<Part> for FromSink<Si, Part, F>
+where
+    Si: Sink<Part>,
+    F: FnMut(Pin<&mut Si>) -> T,
+{
+    type Error = Si::Error;
+    type Output = T;
+    type Recv = ();
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().sink.poll_ready(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        self.project().sink.start_send(part)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().sink.poll_flush(cx)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        let mut this = self.project();
+        ready!(this.sink.as_mut().poll_close(cx))?;
+        // A closed sink cannot be written to again.
+        *this.is_terminated = true;
+        Poll::Ready(Ok((this.f)(this.sink)))
+    }
+}
+
+impl<Si: Debug, Part, F> Debug for FromSink<Si, Part, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromSink")
+            .field("sink", &self.sink)
+            .field("is_terminated", &self.is_terminated)
+            .finish()
+    }
+}
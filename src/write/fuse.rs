@@ -5,7 +5,7 @@ use std::task::{Context, Poll};
 
 use futures_core::ready;
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`fuse`](super::MultipartWriteExt::fuse).
@@ -114,6 +114,22 @@ where
     }
 }
 
+impl<Wr, Part, F> AbortMultipartWrite<Part> for Fuse<Wr, Part, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(&Wr::Output) -> bool,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        if self.is_terminated {
+            return Poll::Ready(Ok(()));
+        }
+        self.project().writer.as_mut().poll_abort(cx)
+    }
+}
+
 impl<Wr, Part, F> Debug for Fuse<Wr, Part, F>
 where
     Wr: Debug,
@@ -0,0 +1,223 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// What [`guarded`](super::MultipartWriteExt::guarded) does when the writer is
+/// dropped in the middle of a write.
+///
+/// A write is in progress from the time a part is sent until the writer is
+/// completed or aborted successfully.
+pub enum DropPolicy<Wr> {
+    /// Call the function with a message saying which writer was dropped, so
+    /// that it can be logged.
+    Log(fn(&str)),
+    /// Panic in debug builds, and do nothing in release builds.
+    ///
+    /// This does not panic if the writer is dropped while the thread is
+    /// already panicking.
+    Panic,
+    /// Call the function with the writer so that it can be cleaned up.
+    Cleanup(Box<dyn FnOnce(Pin<Box<Wr>>) + Send>),
+}
+
+impl<Wr> DropPolicy<Wr> {
+    /// Clean up by calling `f` with the writer.
+    pub fn cleanup<F>(f: F) -> Self
+    where
+        F: FnOnce(Pin<Box<Wr>>) + Send + 'static,
+    {
+        Self::Cleanup(Box::new(f))
+    }
+
+    /// Clean up by aborting the writer in a task spawned on the current
+    /// `tokio` runtime.
+    ///
+    /// If the writer is not dropped in the context of a runtime, it is
+    /// dropped without being aborted.  Errors from aborting the writer are
+    /// ignored.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn spawn_abort<Part>() -> Self
+    where
+        Wr: AbortMultipartWrite<Part> + Send + 'static,
+        Part: 'static,
+    {
+        Self::cleanup(|mut writer| {
+            let Ok(handle) = tokio::runtime::Handle::try_current() else {
+                return;
+            };
+            handle.spawn(async move {
+                let abort = std::future::poll_fn(|cx| {
+                    AbortMultipartWrite::<Part>::poll_abort(writer.as_mut(), cx)
+                });
+                let _ = abort.await;
+            });
+        })
+    }
+}
+
+impl<Wr> Debug for DropPolicy<Wr> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Log(_) => f.write_str("Log(..)"),
+            Self::Panic => f.write_str("Panic"),
+            Self::Cleanup(_) => f.write_str("Cleanup(..)"),
+        }
+    }
+}
+
+fn message<Wr>() -> String {
+    format!(
+        "writer `{}` dropped before completion",
+        std::any::type_name::<Wr>(),
+    )
+}
+
+/// `MultipartWrite` for [`guarded`](super::MultipartWriteExt::guarded).
+#[must_use = "futures do nothing unless polled"]
+pub struct Guarded<Wr, Part> {
+    writer: Option<Pin<Box<Wr>>>,
+    policy: Option<DropPolicy<Wr>>,
+    in_progress: bool,
+    _p: PhantomData<fn(Part)>,
+}
+
+impl<Wr, Part> Guarded<Wr, Part> {
+    pub(super) fn new(writer: Wr, policy: DropPolicy<Wr>) -> Self {
+        Self {
+            writer: Some(Box::pin(writer)),
+            policy: Some(policy),
+            in_progress: false,
+            _p: PhantomData,
+        }
+    }
+
+    /// Returns `true` if parts have been sent since the writer was last
+    /// completed or aborted.
+    pub fn in_progress(&self) -> bool {
+        self.in_progress
+    }
+
+    /// Consumes `Guarded`, returning the underlying writer without applying
+    /// the policy.
+    pub fn into_inner(mut self) -> Pin<Box<Wr>> {
+        self.writer.take().unwrap()
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        self.writer.as_ref().unwrap()
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(&mut self) -> Pin<&mut Wr> {
+        self.writer.as_mut().unwrap().as_mut()
+    }
+}
+
+// The writer is pinned on the heap so that it can be moved out when dropped.
+impl<Wr, Part> Unpin for Guarded<Wr, Part> {}
+
+impl<Wr, Part> Drop for Guarded<Wr, Part> {
+    fn drop(&mut self) {
+        let Some(writer) = self.writer.take() else {
+            return;
+        };
+        if !self.in_progress {
+            return;
+        }
+        match self.policy.take() {
+            Some(DropPolicy::Log(log)) => log(&message::<Wr>()),
+            Some(DropPolicy::Panic)
+                if cfg!(debug_assertions) && !std::thread::panicking() =>
+            {
+                panic!("{}", message::<Wr>());
+            },
+            Some(DropPolicy::Cleanup(f)) => f(writer),
+            _ => {},
+        }
+    }
+}
+
+impl<Wr, Part> FusedMultipartWrite<Part> for Guarded<Wr, Part>
+where
+    Wr: FusedMultipartWrite<Part>,
+{
+    fn is_terminated(&self) -> bool {
+        self.get_ref().is_terminated()
+    }
+}
+
+impl<Wr, Part> MultipartWrite<Part> for Guarded<Wr, Part>
+where
+    Wr: MultipartWrite<Part>,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Wr::Recv;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().get_pin_mut().poll_ready(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.get_mut();
+        this.in_progress = true;
+        this.get_pin_mut().start_send(part)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().get_pin_mut().poll_flush(cx)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        let this = self.get_mut();
+        let out = ready!(this.get_pin_mut().poll_complete(cx))?;
+        this.in_progress = false;
+        Poll::Ready(Ok(out))
+    }
+}
+
+impl<Wr, Part> AbortMultipartWrite<Part> for Guarded<Wr, Part>
+where
+    Wr: AbortMultipartWrite<Part>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.get_pin_mut().poll_abort(cx))?;
+        this.in_progress = false;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Wr: Debug, Part> Debug for Guarded<Wr, Part> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Guarded")
+            .field("writer", &self.writer)
+            .field("policy", &self.policy)
+            .field("in_progress", &self.in_progress)
+            .finish()
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+use futures_sink::Sink;
+
+use crate::MultipartWrite;
+
+pin_project_lite::pin_project! {
+    /// `Sink` for [`into_sink`](super::MultipartWriteExt::into_sink).
+    #[must_use = "sinks do nothing unless polled"]
+    pub struct IntoSink<Wr: MultipartWrite<Part>, Part> {
+        #[pin]
+        writer: Wr,
+        output: Option<Wr::Output>,
+        closed: bool,
+    }
+}
+
+impl<Wr: MultipartWrite<Part>, Part> IntoSink<Wr, Part> {
+    pub(super) fn new(writer: Wr) -> Self {
+        Self { writer, output: None, closed: false }
+    }
+
+    /// Consumes `IntoSink`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Consumes `IntoSink`, returning the output of the underlying writer if
+    /// the sink has been closed.
+    pub fn into_output(self) -> Option<Wr::Output> {
+        self.output
+    }
+
+    /// Acquires a reference to the output of the underlying writer if the sink
+    /// has been closed.
+    pub fn output(&self) -> Option<&Wr::Output> {
+        self.output.as_ref()
+    }
+
+    /// Takes the output of the underlying writer if the sink has been closed.
+    pub fn take_output(self: Pin<&mut Self>) -> Option<Wr::Output> {
+        self.project().output.take()
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+}
+
+impl<Wr: MultipartWrite<Part>, Part> Sink<Part> for IntoSink<Wr, Part> {
+    type Error = Wr::Error;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Part) -> Result<(), Self::Error> {
+        self.project().writer.start_send(item).map(|_| ())
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_flush(cx)
+    }
+
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        // The sink may be closed more than once, but the writer should only be
+        // completed the first time, even if the output was taken since.
+        if !*this.closed {
+            let out = ready!(this.writer.poll_complete(cx))?;
+            *this.output = Some(out);
+            *this.closed = true;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Wr, Part> Debug for IntoSink<Wr, Part>
+where
+    Wr: MultipartWrite<Part> + Debug,
+    Wr::Output: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntoSink")
+            .field("writer", &self.writer)
+            .field("output", &self.output)
+            .field("closed", &self.closed)
+            .finish()
+    }
+}
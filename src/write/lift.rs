@@ -5,7 +5,7 @@ use std::task::{Context, Poll};
 
 use futures_core::ready;
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`lift`](super::MultipartWriteExt::lift).
@@ -143,6 +143,26 @@ where
     }
 }
 
+impl<Wr, U, P, Part> AbortMultipartWrite<P> for Lift<Wr, U, P, Part>
+where
+    U: AbortMultipartWrite<P, Output = Part>,
+    Wr: AbortMultipartWrite<Part>,
+    Wr::Error: From<U::Error>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        // The part that was completed by the outer writer is abandoned along
+        // with the rest of the write.
+        *this.buffered = None;
+        let ready1 = this.writer.poll_abort(cx).map_err(Wr::Error::from);
+        let ready2 = this.inner.poll_abort(cx);
+        super::fanout::join(ready1, ready2)
+    }
+}
+
 impl<Wr, U, P, Part> Debug for Lift<Wr, U, P, Part>
 where
     Wr: Debug,
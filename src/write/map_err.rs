@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`map_err`](super::MultipartWriteExt::map_err).
@@ -110,6 +110,23 @@ where
     }
 }
 
+impl<Wr, Part, E, F> AbortMultipartWrite<Part> for MapErr<Wr, Part, E, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(Wr::Error) -> E,
+{
+    fn poll_abort(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.as_mut()
+            .project()
+            .writer
+            .poll_abort(cx)
+            .map_err(self.as_mut().project().f)
+    }
+}
+
 impl<Wr: Debug, Part, E, F> Debug for MapErr<Wr, Part, E, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("MapErr").field("writer", &self.writer).finish()
@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`map_ok`](super::MultipartWriteExt::map_ok).
@@ -98,6 +98,19 @@ where
     }
 }
 
+impl<Wr, Part, T, F> AbortMultipartWrite<Part> for MapOk<Wr, Part, T, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(Wr::Output) -> T,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_abort(cx)
+    }
+}
+
 impl<Wr: Debug, Part, T, F> Debug for MapOk<Wr, Part, T, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("MapOk").field("writer", &self.writer).finish()
@@ -0,0 +1,117 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`map_part`].
+    ///
+    /// [`map_part`]: super::MultipartWriteExt::map_part
+    #[must_use = "futures do nothing unless polled"]
+    pub struct MapPart<Wr, Part, P, F> {
+        #[pin]
+        writer: Wr,
+        f: F,
+        _p: PhantomData<fn(P) -> Part>,
+    }
+}
+
+impl<Wr, Part, P, F> MapPart<Wr, Part, P, F> {
+    pub(super) fn new(writer: Wr, f: F) -> Self {
+        Self { writer, f, _p: PhantomData }
+    }
+
+    /// Consumes `MapPart`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+}
+
+impl<Wr, Part, P, F> FusedMultipartWrite<P> for MapPart<Wr, Part, P, F>
+where
+    Wr: FusedMultipartWrite<Part>,
+    F: FnMut(P) -> Part,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr, Part, P, F> MultipartWrite<P> for MapPart<Wr, Part, P, F>
+where
+    Wr: MultipartWrite<Part>,
+    F: FnMut(P) -> Part,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Wr::Recv;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_ready(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: P,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        this.writer.start_send((this.f)(part))
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_flush(cx)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        self.project().writer.poll_complete(cx)
+    }
+}
+
+impl<Wr, Part, P, F> AbortMultipartWrite<P> for MapPart<Wr, Part, P, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(P) -> Part,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_abort(cx)
+    }
+}
+
+impl<Wr: Debug, Part, P, F> Debug for MapPart<Wr, Part, P, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapPart").field("writer", &self.writer).finish()
+    }
+}
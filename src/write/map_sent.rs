@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`map_sent`](super::MultipartWriteExt::map_sent).
@@ -98,6 +98,19 @@ where
     }
 }
 
+impl<Wr, Part, R, F> AbortMultipartWrite<Part> for MapSent<Wr, Part, R, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(Wr::Recv) -> R,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_abort(cx)
+    }
+}
+
 impl<Wr: Debug, Part, R, F> Debug for MapSent<Wr, Part, R, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("MapSent").field("writer", &self.writer).finish()
@@ -3,26 +3,55 @@
 //! This module contains the trait [`MultipartWriteExt`], which provides
 //! adapters for chaining and composing writers.
 use crate::{
-    BoxFusedMultipartWrite, BoxMultipartWrite, FusedMultipartWrite,
-    LocalBoxFusedMultipartWrite, LocalBoxMultipartWrite, MultipartWrite,
+    AbortMultipartWrite, BoxFusedMultipartWrite, BoxMultipartWrite, Either,
+    FusedMultipartWrite, LocalBoxFusedMultipartWrite, LocalBoxMultipartWrite,
+    MakeMultipartWrite, MultipartWrite,
 };
 
 use futures_core::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+mod abort;
+pub use abort::Abort;
+pub(crate) use abort::{PollAbort, poll_abort};
+
+mod and_then_part;
+pub use and_then_part::AndThenPart;
+
 mod buffered;
 pub use buffered::Buffered;
 
+mod chunks;
+pub use chunks::{Chunks, chunks};
+
+#[cfg(feature = "tokio")]
+mod circuit_breaker;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[doc(inline)]
+pub use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerConfig, CircuitOpen, CircuitState,
+};
+
 mod complete;
 pub use complete::Complete;
 
+mod dead_letter;
+pub use dead_letter::DeadLetter;
+
 mod extend;
 pub use extend::{Extend, extend, extend_default};
 
 mod fanout;
 pub use fanout::Fanout;
 
+mod fanout_all;
+pub use fanout_all::{
+    AbortBranches, FanoutAll, FanoutBranches, FanoutTuple, FusedBranches,
+    fanout_all, fanout_tuple,
+};
+
 mod feed;
 pub use feed::Feed;
 
@@ -41,9 +70,26 @@ pub use fold_sent::FoldSent;
 mod for_each_recv;
 pub use for_each_recv::ForEachRecv;
 
+#[cfg(feature = "futures-sink")]
+mod from_sink;
+#[cfg(feature = "futures-sink")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-sink")))]
+#[doc(inline)]
+pub use from_sink::{FromSink, from_sink, from_sink_with};
+
 mod fuse;
 pub use fuse::Fuse;
 
+mod guarded;
+pub use guarded::{DropPolicy, Guarded};
+
+#[cfg(feature = "futures-sink")]
+mod into_sink;
+#[cfg(feature = "futures-sink")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-sink")))]
+#[doc(inline)]
+pub use into_sink::IntoSink;
+
 mod lift;
 pub use lift::Lift;
 
@@ -53,23 +99,104 @@ pub use map_err::MapErr;
 mod map_ok;
 pub use map_ok::MapOk;
 
+mod map_part;
+pub use map_part::MapPart;
+
 mod map_sent;
 pub use map_sent::MapSent;
 
+mod or_else_switch;
+pub use or_else_switch::OrElseSwitch;
+
+mod parallel;
+pub use parallel::{Parallel, parallel};
+
+mod poison_on_error;
+pub use poison_on_error::PoisonOnError;
+
+#[cfg(feature = "tokio")]
+mod rate_limit;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[doc(inline)]
+pub use rate_limit::{Quota, RateLimit};
+
 mod ready_part;
 pub use ready_part::ReadyPart;
 
+mod ready_part_concurrent;
+pub use ready_part_concurrent::ReadyPartConcurrent;
+
+#[cfg(feature = "tokio")]
+mod retry;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[doc(inline)]
+pub use retry::{ExponentialBackoff, Retry, RetryIf, RetryPolicy};
+
+mod rolling;
+pub use rolling::{RollPolicy, Rolling, rolling, rolling_by};
+
+mod route;
+pub use route::Route;
+
 mod send_flush;
 pub use send_flush::SendFlush;
 
 mod then;
 pub use then::Then;
 
+#[cfg(feature = "tokio")]
+mod timeout;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[doc(inline)]
+pub use timeout::{Elapsed, Phase, Timeout, TimeoutConfig};
+
+mod transaction;
+pub use transaction::{
+    Transaction, TransactionError, TransactionPhase, transaction,
+};
+
+mod try_map_part;
+pub use try_map_part::TryMapPart;
+
+mod unzip;
+pub use unzip::{Unzip, unzip};
+
 impl<Wr: MultipartWrite<Part>, Part> MultipartWriteExt<Part> for Wr {}
 
 /// An extension trait for `MultipartWrite` providing a variety of convenient
 /// combinator functions.
 pub trait MultipartWriteExt<Part>: MultipartWrite<Part> {
+    /// A future that aborts the write in progress, discarding and cleaning up
+    /// the parts written so far.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init).buffered(2);
+    ///
+    /// writer.send_flush(1).await.unwrap();
+    /// writer.feed(2).await.unwrap();
+    /// writer.abort().await.unwrap();
+    /// writer.send_flush(3).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!(out, vec![3]);
+    /// # })
+    /// ```
+    fn abort(&mut self) -> Abort<'_, Self, Part>
+    where
+        Self: AbortMultipartWrite<Part> + Unpin,
+    {
+        Abort::new(self)
+    }
+
     /// Wrap this writer in a `Box`, pinning it.
     fn boxed<'a>(
         self,
@@ -145,6 +272,59 @@ pub trait MultipartWriteExt<Part>: MultipartWrite<Part> {
         >(Buffered::new(self, capacity.into().unwrap_or_default()))
     }
 
+    /// Stop using this writer for a while when it fails too often, according
+    /// to the given [`CircuitBreakerConfig`].
+    ///
+    /// The results of `poll_ready`, `start_send` and `poll_flush` are recorded
+    /// while the circuit is closed.  When the rate of errors is too high the
+    /// circuit opens, and `poll_ready` either fails with a [`CircuitOpen`]
+    /// converted into the error type of the writer, or waits, until it is
+    /// time to try the writer again.  The circuit is then half-open, and it
+    /// closes when a part is sent or the writer is flushed or completed, or
+    /// opens again on the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use std::time::Duration;
+    ///
+    /// use multipart_write::write::{CircuitBreakerConfig, CircuitState};
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let config = CircuitBreakerConfig::new()
+    ///     .failure_rate(0.5)
+    ///     .min_calls(10)
+    ///     .open_for(Duration::from_secs(5));
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init)
+    ///     .map_err(|e| -> std::io::Error { match e {} })
+    ///     .circuit_breaker(config);
+    ///
+    /// writer.send_flush(1).await.unwrap();
+    /// writer.send_flush(2).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!(out, vec![1, 2]);
+    /// assert_eq!(writer.state(), CircuitState::Closed);
+    /// # })
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn circuit_breaker(
+        self,
+        config: CircuitBreakerConfig,
+    ) -> CircuitBreaker<Self, Part>
+    where
+        Self::Error: From<CircuitOpen>,
+        Self: Sized,
+    {
+        assert_writer::<Part, Self::Recv, Self::Error, Self::Output, _>(
+            CircuitBreaker::new(self, config),
+        )
+    }
+
     /// A future that runs this writer to completion, returning the associated
     /// output.
     fn complete(&mut self) -> Complete<'_, Self, Part>
@@ -154,10 +334,70 @@ pub trait MultipartWriteExt<Part>: MultipartWrite<Part> {
         Complete::new(self)
     }
 
+    /// Send the parts that this writer fails to write to a dead letter writer
+    /// instead of returning the error.
+    ///
+    /// When `start_send` returns an error that `classify` says is specific to
+    /// the part, the part is sent to `dlq` with the error and the write goes
+    /// on, with `None` as the value returned for the part.  Other errors are
+    /// returned as they are.  Completing the writer completes both writers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let dlq: Vec<(&str, String)> = Vec::new();
+    /// let mut writer = write::extend(init)
+    ///     .map_err(|e| -> String { match e {} })
+    ///     .try_map_part(|s: &str| s.parse::<u8>().map_err(|e| e.to_string()))
+    ///     .dead_letter(
+    ///         write::extend(dlq).map_err(|e| -> String { match e {} }),
+    ///         |_| true,
+    ///     );
+    ///
+    /// writer.send_flush("1").await.unwrap();
+    /// let ret = writer.send_flush("a").await.unwrap();
+    /// writer.send_flush("2").await.unwrap();
+    /// let (out, dead) = writer.complete().await.unwrap();
+    ///
+    /// assert!(ret.is_none());
+    /// assert_eq!(out, vec![1, 2]);
+    /// assert_eq!(dead.len(), 1);
+    /// assert_eq!(dead[0].0, "a");
+    /// # })
+    /// ```
+    fn dead_letter<D, F>(
+        self,
+        dlq: D,
+        classify: F,
+    ) -> DeadLetter<Self, D, Part, F>
+    where
+        Part: Clone,
+        D: MultipartWrite<(Part, Self::Error)>,
+        F: FnMut(&Self::Error) -> bool,
+        Self::Error: From<D::Error>,
+        Self: Sized,
+    {
+        assert_writer::<
+            Part,
+            Option<Self::Recv>,
+            Self::Error,
+            (Self::Output, D::Output),
+            _,
+        >(DeadLetter::new(self, dlq, classify))
+    }
+
     /// Fanout the part to multiple writers.
     ///
     /// This adapter clones each incoming part and forwards it to both writers.
     ///
+    /// If one writer fails to complete, the output of the other is kept and
+    /// completing again only completes the writer that failed.  Aborting
+    /// discards it.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -384,6 +624,83 @@ pub trait MultipartWriteExt<Part>: MultipartWrite<Part> {
         >(Fuse::new(self, f))
     }
 
+    /// Returns a new writer that applies the [`DropPolicy`] if it is dropped
+    /// in the middle of a write.
+    ///
+    /// A write is in progress from the time a part is sent until
+    /// `poll_complete`, or `poll_abort` if the writer can be aborted, returns
+    /// `Poll::Ready`.  Dropping the writer at any other time does nothing.
+    /// This can be used to detect or clean up after a writer that was dropped
+    /// because the task using it was cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// use multipart_write::write::DropPolicy;
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let dropped = Arc::new(AtomicBool::new(false));
+    /// let flag = Arc::clone(&dropped);
+    /// let policy = DropPolicy::cleanup(move |_writer| {
+    ///     flag.store(true, Ordering::SeqCst);
+    /// });
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init).guarded(policy);
+    /// writer.send_flush(1).await.unwrap();
+    /// drop(writer);
+    ///
+    /// assert!(dropped.load(Ordering::SeqCst));
+    /// # })
+    /// ```
+    fn guarded(self, policy: DropPolicy<Self>) -> Guarded<Self, Part>
+    where
+        Self: Sized,
+    {
+        assert_writer::<Part, Self::Recv, Self::Error, Self::Output, _>(
+            Guarded::new(self, policy),
+        )
+    }
+
+    /// Convert this writer into a [`Sink`].
+    ///
+    /// The returned `Sink` discards the values returned by `start_send`.
+    /// Closing the sink completes the writer, and the output is kept to be
+    /// retrieved later with [`IntoSink::output`] or [`IntoSink::take_output`].
+    ///
+    /// [`Sink`]: futures_sink::Sink
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use futures::sink::SinkExt as _;
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut sink = write::extend(init).into_sink();
+    ///
+    /// sink.send(1).await.unwrap();
+    /// sink.send(2).await.unwrap();
+    /// sink.send(3).await.unwrap();
+    /// sink.close().await.unwrap();
+    ///
+    /// assert_eq!(sink.into_output(), Some(vec![1, 2, 3]));
+    /// # })
+    /// ```
+    #[cfg(feature = "futures-sink")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures-sink")))]
+    fn into_sink(self) -> IntoSink<Self, Part>
+    where
+        Self: Sized,
+    {
+        IntoSink::new(self)
+    }
+
     /// Produce the parts for this writer from the output of another writer.
     ///
     /// # Examples
@@ -480,6 +797,234 @@ pub trait MultipartWriteExt<Part>: MultipartWrite<Part> {
         ))
     }
 
+    /// Map the input to a part for this writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<String> = Vec::new();
+    /// let mut writer = write::extend(init).map_part(|n: u8| n.to_string());
+    ///
+    /// writer.send_flush(1).await.unwrap();
+    /// writer.send_flush(2).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!(out, vec!["1".to_string(), "2".to_string()]);
+    /// # })
+    /// ```
+    fn map_part<P, F>(self, f: F) -> MapPart<Self, Part, P, F>
+    where
+        F: FnMut(P) -> Part,
+        Self: Sized,
+    {
+        assert_writer::<P, Self::Recv, Self::Error, Self::Output, _>(
+            MapPart::new(self, f),
+        )
+    }
+
+    /// Attempt to map the input to a part for this writer, returning the error
+    /// from `start_send` if the mapping fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init)
+    ///     .map_err(|e| -> String { match e {} })
+    ///     .try_map_part(|s: &str| s.parse::<u8>().map_err(|e| e.to_string()));
+    ///
+    /// writer.send_flush("1").await.unwrap();
+    /// let res = writer.send_flush("a").await;
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert!(res.is_err());
+    /// assert_eq!(out, vec![1]);
+    /// # })
+    /// ```
+    fn try_map_part<P, E, F>(self, f: F) -> TryMapPart<Self, Part, P, E, F>
+    where
+        F: FnMut(P) -> Result<Part, E>,
+        E: Into<Self::Error>,
+        Self: Sized,
+    {
+        assert_writer::<P, Self::Recv, Self::Error, Self::Output, _>(
+            TryMapPart::new(self, f),
+        )
+    }
+
+    /// Map the input to a part for this writer with a future.
+    ///
+    /// The value returned by sending a part lags one part behind: it is the
+    /// value that this writer returned for the *previous* part, and `None` if
+    /// there is none.  This is because the future for a part is only awaited
+    /// when the writer is next polled, and the part is sent to this writer
+    /// before the writer is ready again.  The value for the last part can be
+    /// taken with [`AndThenPart::take_recv`] after the writer has been
+    /// flushed or completed, and is otherwise returned by the next send.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use std::pin::Pin;
+    ///
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init)
+    ///     .map_sent(|()| "sent")
+    ///     .and_then_part(|n: u8| futures::future::ready(Ok(n + 1)));
+    ///
+    /// let r1 = writer.send_flush(1).await.unwrap();
+    /// let r2 = writer.send_flush(2).await.unwrap();
+    /// let last = Pin::new(&mut writer).take_recv();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!((r1, r2, last), (None, Some("sent"), Some("sent")));
+    /// assert_eq!(out, vec![2, 3]);
+    /// # })
+    /// ```
+    fn and_then_part<P, Fut, F>(
+        self,
+        f: F,
+    ) -> AndThenPart<Self, Part, P, Fut, F>
+    where
+        F: FnMut(P) -> Fut,
+        Fut: Future<Output = Result<Part, Self::Error>>,
+        Self: Sized,
+    {
+        assert_writer::<P, Option<Self::Recv>, Self::Error, Self::Output, _>(
+            AndThenPart::new(self, f),
+        )
+    }
+
+    /// Switch to a fallback writer if this writer fails before the write is
+    /// completed.
+    ///
+    /// The parts of the current write are kept, and when this writer returns
+    /// an error the fallback writer is made with `make_fallback`, the kept
+    /// parts are replayed to it, and every part after that is written to the
+    /// fallback writer.  The output says which of the writers produced it.
+    /// Parts are kept until this writer is flushed, since what was flushed
+    /// does not need to be written again.
+    ///
+    /// At most [`replay_capacity`] parts are kept.  If this writer fails
+    /// after more parts than that were sent, the error is returned instead
+    /// of switching, which [`is_replayable`] tells ahead of time.  Parts
+    /// written while the fallback is being made return `None`, and what the
+    /// fallback writer returned for the replayed parts can be taken with
+    /// [`take_replayed`].
+    ///
+    /// The switch is permanent: once the fallback writer is made, it is used
+    /// for this and every later write, and the primary writer is not written
+    /// to again.  The primary writer is left as it was when it failed, unless
+    /// [`abort_on_switch`] is used to abort it as part of switching.
+    /// Aborting the returned writer aborts both writers, except for a primary
+    /// writer that was already aborted when switching.
+    ///
+    /// [`replay_capacity`]: OrElseSwitch::replay_capacity
+    /// [`is_replayable`]: OrElseSwitch::is_replayable
+    /// [`take_replayed`]: OrElseSwitch::take_replayed
+    /// [`abort_on_switch`]: OrElseSwitch::abort_on_switch
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use futures::future;
+    /// use multipart_write::{Either, MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init)
+    ///     .map_err(|e| -> String { match e {} })
+    ///     .try_map_part(|n: u8| {
+    ///         if n < 3 { Ok(n) } else { Err("primary failed".to_string()) }
+    ///     })
+    ///     .or_else_switch(|| {
+    ///         let init: Vec<u8> = Vec::new();
+    ///         future::ready(Ok(
+    ///             write::extend(init).map_err(|e| -> String { match e {} })
+    ///         ))
+    ///     })
+    ///     .replay_capacity(16);
+    ///
+    /// writer.feed(1).await.unwrap();
+    /// writer.feed(2).await.unwrap();
+    /// writer.feed(3).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!(out, Either::Right(vec![1, 2, 3]));
+    /// # })
+    /// ```
+    fn or_else_switch<M>(self, make_fallback: M) -> OrElseSwitch<Self, M, Part>
+    where
+        Part: Clone,
+        M: MakeMultipartWrite<Part>,
+        M::Writer: MultipartWrite<Part, Error = Self::Error>,
+        Self: Sized,
+    {
+        assert_writer::<
+            Part,
+            Option<
+                Either<Self::Recv, <M::Writer as MultipartWrite<Part>>::Recv>,
+            >,
+            Self::Error,
+            Either<Self::Output, <M::Writer as MultipartWrite<Part>>::Output>,
+            _,
+        >(OrElseSwitch::new(self, make_fallback))
+    }
+
+    /// Returns a new writer that is poisoned by the first error it returns.
+    ///
+    /// After an error, every method returns a clone of that error without
+    /// polling the underlying writer again, and the writer reports itself as
+    /// terminated.  This way a writer that could be left in an inconsistent
+    /// state by an error is never used again, and a stream such as
+    /// [`try_complete_when`] ends after returning the error.  Errors that
+    /// are not `Clone` can be wrapped in an `Arc` with [`map_err`].
+    ///
+    /// [`try_complete_when`]: crate::stream::MultipartStreamExt::try_complete_when
+    /// [`map_err`]: Self::map_err
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use multipart_write::{
+    ///     FusedMultipartWrite as _, MultipartWriteExt as _, write,
+    /// };
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init)
+    ///     .map_err(|e| -> String { match e {} })
+    ///     .try_map_part(|s: &str| s.parse::<u8>().map_err(|e| e.to_string()))
+    ///     .poison_on_error();
+    ///
+    /// writer.send_flush("1").await.unwrap();
+    /// let e1 = writer.send_flush("a").await.unwrap_err();
+    /// let e2 = writer.send_flush("2").await.unwrap_err();
+    ///
+    /// assert_eq!(e1, e2);
+    /// assert!(writer.is_terminated());
+    /// assert!(writer.complete().await.is_err());
+    /// # })
+    /// ```
+    fn poison_on_error(self) -> PoisonOnError<Self, Part>
+    where
+        Self: Sized,
+        Self::Error: Clone,
+    {
+        assert_writer::<Part, Self::Recv, Self::Error, Self::Output, _>(
+            PoisonOnError::new(self),
+        )
+    }
+
     /// A convenience method for calling [`MultipartWrite::poll_ready`] on
     /// [`Unpin`] writer types.
     #[must_use = "futures do nothing unless polled"]
@@ -519,6 +1064,78 @@ pub trait MultipartWriteExt<Part>: MultipartWrite<Part> {
         Pin::new(self).poll_complete(cx)
     }
 
+    /// Limit the rate at which parts can be sent to this writer to the given
+    /// [`Quota`].
+    ///
+    /// Each part uses one unit of the quota.  When the quota is used up,
+    /// `poll_ready` returns `Pending` until it has been replenished.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use multipart_write::write::Quota;
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init).rate_limit(Quota::per_second(100));
+    ///
+    /// writer.send_flush(1).await.unwrap();
+    /// writer.send_flush(2).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!(out, vec![1, 2]);
+    /// # })
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn rate_limit(self, quota: Quota) -> RateLimit<Self, Part, fn(&Part) -> u64>
+    where
+        Self: Sized,
+    {
+        assert_writer::<Part, Self::Recv, Self::Error, Self::Output, _>(
+            RateLimit::new(self, quota, |_| 1),
+        )
+    }
+
+    /// Limit the rate at which parts can be sent to this writer to the given
+    /// [`Quota`], where each part uses the number of units returned by `f`.
+    ///
+    /// This can be used for a quota of bytes rather than parts.  A part that
+    /// weighs more than what is left of the quota is still sent as soon as
+    /// any of the quota is available, and the writer is not ready again until
+    /// the difference has been replenished.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use multipart_write::write::Quota;
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<String> = Vec::new();
+    /// let quota = Quota::per_second(1024 * 1024);
+    /// let mut writer =
+    ///     write::extend(init).rate_limit_by(quota, |s: &String| s.len() as u64);
+    ///
+    /// writer.send_flush("hello".to_string()).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!(out, vec!["hello".to_string()]);
+    /// # })
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn rate_limit_by<F>(self, quota: Quota, f: F) -> RateLimit<Self, Part, F>
+    where
+        F: FnMut(&Part) -> u64,
+        Self: Sized,
+    {
+        assert_writer::<Part, Self::Recv, Self::Error, Self::Output, _>(
+            RateLimit::new(self, quota, f),
+        )
+    }
+
     /// Provide a part to this writer in the output of a future.
     ///
     /// The result is a new writer over the type `U` that passes each value
@@ -554,6 +1171,200 @@ pub trait MultipartWriteExt<Part>: MultipartWrite<Part> {
         ))
     }
 
+    /// Provide parts to this writer in the output of futures, running up to
+    /// `limit` of them at once.
+    ///
+    /// This is like [`ready_part`](Self::ready_part), except that a new part
+    /// can be accepted while the futures for previous parts are still running.
+    /// The resolved parts are sent to the inner writer in the same order that
+    /// they were provided in.  Flushing or completing the writer waits for all
+    /// of the futures in flight.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init)
+    ///     .ready_part_concurrent(2, |n: u8| async move { Ok(n * 10) });
+    ///
+    /// writer.feed(1).await.unwrap();
+    /// writer.feed(2).await.unwrap();
+    /// writer.feed(3).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!(out, vec![10, 20, 30]);
+    /// # })
+    /// ```
+    fn ready_part_concurrent<P, Fut, F>(
+        self,
+        limit: usize,
+        f: F,
+    ) -> ReadyPartConcurrent<Self, Part, P, Fut, F>
+    where
+        F: FnMut(P) -> Fut,
+        Fut: Future<Output = Result<Part, Self::Error>>,
+        Self: Sized,
+    {
+        assert_writer::<P, (), Self::Error, Self::Output, _>(
+            ReadyPartConcurrent::new(self, limit, true, f),
+        )
+    }
+
+    /// Provide parts to this writer in the output of futures, running up to
+    /// `limit` of them at once and sending each part as soon as it is ready.
+    ///
+    /// This is like [`ready_part_concurrent`](Self::ready_part_concurrent),
+    /// except that the parts are sent to the inner writer in the order that
+    /// their futures finish, rather than the order they were provided in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init)
+    ///     .ready_part_unordered(2, |n: u8| async move { Ok(n * 10) });
+    ///
+    /// writer.feed(1).await.unwrap();
+    /// writer.feed(2).await.unwrap();
+    /// let mut out = writer.complete().await.unwrap();
+    /// out.sort();
+    ///
+    /// assert_eq!(out, vec![10, 20]);
+    /// # })
+    /// ```
+    fn ready_part_unordered<P, Fut, F>(
+        self,
+        limit: usize,
+        f: F,
+    ) -> ReadyPartConcurrent<Self, Part, P, Fut, F>
+    where
+        F: FnMut(P) -> Fut,
+        Fut: Future<Output = Result<Part, Self::Error>>,
+        Self: Sized,
+    {
+        assert_writer::<P, (), Self::Error, Self::Output, _>(
+            ReadyPartConcurrent::new(self, limit, false, f),
+        )
+    }
+
+    /// Retry the operations of this writer that fail with an error, according
+    /// to the given [`RetryPolicy`].
+    ///
+    /// A copy of each part is kept while it is being sent, so that if
+    /// `start_send` fails the part can be sent again.  In that case the value
+    /// returned is `None`, the part is sent again the next time the writer is
+    /// polled, and an error is only returned from that call if the policy
+    /// decides not to retry.  The value returned when the part is sent can
+    /// then be taken with [`Retry::take_recv`].
+    ///
+    /// Errors from completing the writer are only retried if the policy opts
+    /// in with [`RetryPolicy::retries_complete`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use std::time::Duration;
+    ///
+    /// use multipart_write::write::ExponentialBackoff;
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let policy = ExponentialBackoff::new(Duration::from_millis(10))
+    ///     .max_retries(5)
+    ///     .jitter()
+    ///     .retry_if(|e: &std::io::Error| {
+    ///         e.kind() == std::io::ErrorKind::Interrupted
+    ///     });
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init).map_err(|e| match e {}).retry(policy);
+    ///
+    /// let r1 = writer.send_flush(1).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!(r1, Some(()));
+    /// assert_eq!(out, vec![1]);
+    /// # })
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn retry<P>(self, policy: P) -> Retry<Self, Part, P>
+    where
+        P: RetryPolicy<Self::Error>,
+        Part: Clone,
+        Self: Sized,
+    {
+        assert_writer::<Part, Option<Self::Recv>, Self::Error, Self::Output, _>(
+            Retry::new(self, policy),
+        )
+    }
+
+    /// Send each part to either this writer or another writer, depending on
+    /// the result of the given predicate.
+    ///
+    /// Parts for which `f` returns `true` are sent to this writer, and the
+    /// rest are sent to `other`.  Completing the writer completes both of
+    /// them.
+    ///
+    /// The value returned by sending a part says which of the two writers
+    /// received it.
+    ///
+    /// Since the part is not known when the writer is polled for readiness,
+    /// `poll_ready` waits on both writers.  A writer that is ready is not
+    /// polled again until it has been sent a part, so a writer that does not
+    /// have parts routed to it only applies backpressure once.  To wait only
+    /// on the writer a part is routed to, use [`Route::poll_ready_part`]
+    /// before sending it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # futures::executor::block_on(async {
+    /// use multipart_write::{Either, MultipartWriteExt as _, write};
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let evens = write::extend(init.clone());
+    /// let odds = write::extend(init);
+    ///
+    /// let mut writer = evens.route(odds, |n| n % 2 == 0);
+    /// let r1 = writer.send_flush(1).await.unwrap();
+    /// let r2 = writer.send_flush(2).await.unwrap();
+    /// writer.send_flush(3).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!((r1, r2), (Either::Right(()), Either::Left(())));
+    /// assert_eq!(out, (vec![2], vec![1, 3]));
+    /// # })
+    /// ```
+    fn route<U, F>(self, other: U, f: F) -> Route<Self, U, Part, F>
+    where
+        F: FnMut(&Part) -> bool,
+        U: MultipartWrite<Part, Error = Self::Error>,
+        Self: Sized,
+    {
+        assert_writer::<
+            Part,
+            Either<Self::Recv, U::Recv>,
+            Self::Error,
+            (Self::Output, U::Output),
+            _,
+        >(Route::new(self, other, f))
+    }
+
     /// A future that completes when a part has been fully processed into the
     /// writer, including flushing.
     fn send_flush(&mut self, part: Part) -> SendFlush<'_, Self, Part>
@@ -593,6 +1404,55 @@ pub trait MultipartWriteExt<Part>: MultipartWrite<Part> {
     {
         assert_writer::<Part, Self::Recv, Self::Error, T, _>(Then::new(self, f))
     }
+
+    /// Fail with an error if this writer takes too long to become ready, to
+    /// flush, or to complete, according to the given [`TimeoutConfig`].
+    ///
+    /// Each limit applies to one call of the corresponding method, measured
+    /// from the first time it is polled until it returns a result.  The
+    /// optional deadline applies to the whole write, measured from the first
+    /// time the writer is polled until it is completed.
+    ///
+    /// The error is an [`Elapsed`] converted into the error type of the
+    /// writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use std::time::Duration;
+    ///
+    /// use multipart_write::write::TimeoutConfig;
+    /// use multipart_write::{MultipartWriteExt as _, write};
+    ///
+    /// let config = TimeoutConfig::new()
+    ///     .ready(Duration::from_secs(1))
+    ///     .complete(Duration::from_secs(5))
+    ///     .deadline(Duration::from_secs(60));
+    ///
+    /// let init: Vec<u8> = Vec::new();
+    /// let mut writer = write::extend(init)
+    ///     .map_err(|e| -> std::io::Error { match e {} })
+    ///     .timeout(config);
+    ///
+    /// writer.send_flush(1).await.unwrap();
+    /// writer.send_flush(2).await.unwrap();
+    /// let out = writer.complete().await.unwrap();
+    ///
+    /// assert_eq!(out, vec![1, 2]);
+    /// # })
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn timeout(self, config: TimeoutConfig) -> Timeout<Self, Part>
+    where
+        Self::Error: From<Elapsed>,
+        Self: Sized,
+    {
+        assert_writer::<Part, Self::Recv, Self::Error, Self::Output, _>(
+            Timeout::new(self, config),
+        )
+    }
 }
 
 fn assert_writer<Part, R, E, T, Wr>(wr: Wr) -> Wr
@@ -0,0 +1,355 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use super::abort::{PollAbort, poll_abort};
+use super::fanout::join;
+use crate::{
+    AbortMultipartWrite, Either, FusedMultipartWrite, MakeMultipartWrite,
+    MultipartWrite,
+};
+
+/// The number of parts kept for replay unless it is changed with
+/// [`OrElseSwitch::replay_capacity`].
+const DEFAULT_REPLAY_CAPACITY: usize = 128;
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for
+    /// [`or_else_switch`](super::MultipartWriteExt::or_else_switch).
+    #[must_use = "futures do nothing unless polled"]
+    pub struct OrElseSwitch<Wr, M: MakeMultipartWrite<Part>, Part> {
+        #[pin]
+        writer: Wr,
+        abort: Option<PollAbort<Wr>>,
+        aborting: bool,
+        make: M,
+        #[pin]
+        making: Option<M::Future>,
+        #[pin]
+        fallback: Option<M::Writer>,
+        replay: VecDeque<Part>,
+        replayed: Vec<<M::Writer as MultipartWrite<Part>>::Recv>,
+        capacity: usize,
+        overflowed: bool,
+        switching: bool,
+    }
+}
+
+impl<Wr, M: MakeMultipartWrite<Part>, Part> OrElseSwitch<Wr, M, Part> {
+    pub(super) fn new(writer: Wr, make: M) -> Self {
+        Self {
+            writer,
+            abort: None,
+            aborting: false,
+            make,
+            making: None,
+            fallback: None,
+            replay: VecDeque::new(),
+            replayed: Vec::new(),
+            capacity: DEFAULT_REPLAY_CAPACITY,
+            overflowed: false,
+            switching: false,
+        }
+    }
+
+    /// Set the maximum number of parts of the current write that are kept to
+    /// be replayed to the fallback writer.
+    ///
+    /// If the primary writer fails after more parts than this were sent to
+    /// it since it was last flushed, the write cannot be replayed and the
+    /// error is returned instead.
+    /// The default is 128.
+    pub fn replay_capacity(self, capacity: usize) -> Self {
+        Self { capacity, ..self }
+    }
+
+    /// Abort the primary writer when switching to the fallback writer, before
+    /// the fallback writer is made.
+    ///
+    /// An error returned by aborting the primary writer is ignored, since
+    /// the write goes on with the fallback writer.  Since aborting also
+    /// discards what was flushed, the parts of the current write are kept
+    /// for replay until it is completed, rather than until it is flushed.
+    pub fn abort_on_switch(self) -> Self
+    where
+        Wr: AbortMultipartWrite<Part>,
+    {
+        Self { abort: Some(poll_abort::<Wr, Part>), ..self }
+    }
+
+    /// Returns `true` if a failure of the primary writer in the current write
+    /// would switch to the fallback writer.
+    ///
+    /// This is `false` once more parts than the replay capacity were sent in
+    /// the current write, after which a failure is returned as an error.
+    pub fn is_replayable(&self) -> bool {
+        !self.overflowed
+    }
+
+    /// Takes the values returned by the fallback writer for the parts that
+    /// were replayed to it, in the order they were replayed.
+    pub fn take_replayed(
+        self: Pin<&mut Self>,
+    ) -> Vec<<M::Writer as MultipartWrite<Part>>::Recv> {
+        std::mem::take(self.project().replayed)
+    }
+
+    /// Returns `true` if the writer has switched to the fallback writer.
+    pub fn is_switched(&self) -> bool {
+        self.switching || self.fallback.is_some()
+    }
+
+    /// Acquires a reference to the fallback writer, if it has been made.
+    pub fn fallback_ref(&self) -> Option<&M::Writer> {
+        self.fallback.as_ref()
+    }
+
+    /// Consumes `OrElseSwitch`, returning the primary writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the primary writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the primary writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the primary writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Start switching to the fallback writer after the primary writer
+    /// failed, returning the error if the write cannot be replayed.
+    fn switch<E>(self: Pin<&mut Self>, e: E) -> Result<(), E> {
+        let this = self.project();
+        if *this.overflowed {
+            return Err(e);
+        }
+        *this.switching = true;
+        *this.aborting = this.abort.is_some();
+        Ok(())
+    }
+
+    /// Make the fallback writer and replay the kept parts to it.
+    fn poll_switch(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), <M::Writer as MultipartWrite<Part>>::Error>> {
+        let mut this = self.project();
+        if !*this.switching {
+            return Poll::Ready(Ok(()));
+        }
+        if *this.aborting {
+            let abort = this.abort.expect("aborting without an abort hook");
+            ready!(abort(this.writer.as_mut(), cx));
+            *this.aborting = false;
+        }
+        if this.fallback.is_none() {
+            if this.making.is_none() {
+                this.making.set(Some(this.make.make()));
+            }
+            let fut = this.making.as_mut().as_pin_mut().unwrap();
+            let res = ready!(fut.poll(cx));
+            this.making.set(None);
+            this.fallback.set(Some(res?));
+        }
+        let mut fallback = this.fallback.as_mut().as_pin_mut().unwrap();
+        while !this.replay.is_empty() {
+            ready!(fallback.as_mut().poll_ready(cx))?;
+            let part = this.replay.pop_front().unwrap();
+            let ret = fallback.as_mut().start_send(part)?;
+            this.replayed.push(ret);
+        }
+        *this.switching = false;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Wr, M, Part> FusedMultipartWrite<Part> for OrElseSwitch<Wr, M, Part>
+where
+    Part: Clone,
+    Wr: FusedMultipartWrite<Part>,
+    M: MakeMultipartWrite<Part>,
+    M::Writer: FusedMultipartWrite<Part, Error = Wr::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        match &self.fallback {
+            Some(fallback) => fallback.is_terminated(),
+            _ => !self.switching && self.writer.is_terminated(),
+        }
+    }
+}
+
+impl<Wr, M, Part> MultipartWrite<Part> for OrElseSwitch<Wr, M, Part>
+where
+    Part: Clone,
+    Wr: MultipartWrite<Part>,
+    M: MakeMultipartWrite<Part>,
+    M::Writer: MultipartWrite<Part, Error = Wr::Error>,
+{
+    type Error = Wr::Error;
+    type Output =
+        Either<Wr::Output, <M::Writer as MultipartWrite<Part>>::Output>;
+    type Recv =
+        Option<Either<Wr::Recv, <M::Writer as MultipartWrite<Part>>::Recv>>;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        loop {
+            ready!(self.as_mut().poll_switch(cx))?;
+            let this = self.as_mut().project();
+            if let Some(fallback) = this.fallback.as_pin_mut() {
+                return fallback.poll_ready(cx);
+            }
+            match ready!(this.writer.poll_ready(cx)) {
+                Ok(()) => return Poll::Ready(Ok(())),
+                Err(e) => self.as_mut().switch(e)?,
+            }
+        }
+    }
+
+    fn start_send(
+        mut self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.as_mut().project();
+        if let Some(fallback) = this.fallback.as_pin_mut() {
+            return fallback
+                .start_send(part)
+                .map(|ret| Some(Either::Right(ret)));
+        }
+        let kept = !*this.overflowed && this.replay.len() < *this.capacity;
+        if kept {
+            this.replay.push_back(part.clone());
+        } else {
+            *this.overflowed = true;
+            this.replay.clear();
+        }
+        match this.writer.start_send(part) {
+            Ok(ret) => Ok(Some(Either::Left(ret))),
+            // The part is written to the fallback writer when it is replayed.
+            Err(e) => self.switch(e).map(|_| None),
+        }
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        loop {
+            ready!(self.as_mut().poll_switch(cx))?;
+            let this = self.as_mut().project();
+            if let Some(fallback) = this.fallback.as_pin_mut() {
+                return fallback.poll_flush(cx);
+            }
+            match ready!(this.writer.poll_flush(cx)) {
+                Ok(()) => {
+                    // What was flushed is durable, so it doesn't need to be
+                    // replayed, unless the switch would abort it.
+                    if this.abort.is_none() {
+                        this.replay.clear();
+                        *this.overflowed = false;
+                    }
+                    return Poll::Ready(Ok(()));
+                },
+                Err(e) => self.as_mut().switch(e)?,
+            }
+        }
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        loop {
+            ready!(self.as_mut().poll_switch(cx))?;
+            let this = self.as_mut().project();
+            if let Some(fallback) = this.fallback.as_pin_mut() {
+                let out = ready!(fallback.poll_complete(cx))?;
+                return Poll::Ready(Ok(Either::Right(out)));
+            }
+            match ready!(this.writer.poll_complete(cx)) {
+                Ok(out) => {
+                    // The next write starts with nothing to replay.
+                    this.replay.clear();
+                    *this.overflowed = false;
+                    return Poll::Ready(Ok(Either::Left(out)));
+                },
+                Err(e) => self.as_mut().switch(e)?,
+            }
+        }
+    }
+}
+
+impl<Wr, M, Part> AbortMultipartWrite<Part> for OrElseSwitch<Wr, M, Part>
+where
+    Part: Clone,
+    Wr: AbortMultipartWrite<Part>,
+    M: MakeMultipartWrite<Part>,
+    M::Writer: AbortMultipartWrite<Part, Error = Wr::Error>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+        // The primary writer was already aborted if switching got past that.
+        let aborted = this.abort.is_some()
+            && (this.fallback.is_some() || *this.switching && !*this.aborting);
+        // A fallback writer that is still being made has nothing of this
+        // write yet, so only the primary writer is left to clean up.
+        this.making.set(None);
+        this.replay.clear();
+        *this.overflowed = false;
+        *this.switching = false;
+        *this.aborting = false;
+        let res1 = if aborted {
+            Poll::Ready(Ok(()))
+        } else {
+            this.writer.poll_abort(cx)
+        };
+        let res2 = match this.fallback.as_pin_mut() {
+            Some(fallback) => fallback.poll_abort(cx),
+            _ => Poll::Ready(Ok(())),
+        };
+        join(res1, res2)
+    }
+}
+
+impl<Wr, M, Part> Debug for OrElseSwitch<Wr, M, Part>
+where
+    Part: Debug,
+    Wr: Debug,
+    M: MakeMultipartWrite<Part> + Debug,
+    M::Writer: Debug,
+    <M::Writer as MultipartWrite<Part>>::Recv: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrElseSwitch")
+            .field("writer", &self.writer)
+            .field("aborting", &self.aborting)
+            .field("make", &self.make)
+            .field("fallback", &self.fallback)
+            .field("replay", &self.replay)
+            .field("replayed", &self.replayed)
+            .field("capacity", &self.capacity)
+            .field("overflowed", &self.overflowed)
+            .field("switching", &self.switching)
+            .finish()
+    }
+}
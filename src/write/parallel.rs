@@ -0,0 +1,204 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::future::TryFuture;
+use futures_core::ready;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// Returns a `MultipartWrite` that sends each part with its own future,
+/// running up to `limit` of them at once.
+///
+/// The function `f` is called with the part number and the part as soon as the
+/// part is sent, and the part number is the value returned by sending it.  Part
+/// numbers start at 1 and increase by one for each part.  The writer is not
+/// ready while `limit` futures are in flight.
+///
+/// Completing the writer waits for every future and returns their results
+/// with their part numbers, ordered by part number.  The part numbers then
+/// start again from 1.  If a future fails, its error is returned and the
+/// futures for other parts keep running, but the part is missing from the
+/// output, so a write with a failed part is detected by a gap in the part
+/// numbers.
+///
+/// # Panics
+///
+/// Panics if `limit` is zero.
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use multipart_write::{MultipartWriteExt as _, write};
+///
+/// let mut writer = write::parallel(4, |part_number, part: &str| async move {
+///     Ok::<_, std::io::Error>(format!("{part_number}-{part}"))
+/// });
+///
+/// let n1 = writer.send_flush("a").await.unwrap();
+/// let n2 = writer.send_flush("b").await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!((n1, n2), (1, 2));
+/// assert_eq!(out, vec![(1, "1-a".to_string()), (2, "2-b".to_string())]);
+/// # })
+/// ```
+pub fn parallel<Part, Fut, F>(limit: usize, f: F) -> Parallel<Part, Fut, F>
+where
+    F: FnMut(usize, Part) -> Fut,
+    Fut: TryFuture,
+{
+    Parallel::new(limit, f)
+}
+
+/// `MultipartWrite` for [`parallel`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Parallel<Part, Fut: TryFuture, F> {
+    f: F,
+    limit: usize,
+    next: usize,
+    in_flight: Vec<(usize, Pin<Box<Fut>>)>,
+    done: Vec<(usize, Fut::Ok)>,
+    _p: PhantomData<fn(Part)>,
+}
+
+impl<Part, Fut: TryFuture, F> Parallel<Part, Fut, F> {
+    fn new(limit: usize, f: F) -> Self {
+        assert!(limit > 0, "concurrency limit must be non-zero");
+        Self {
+            f,
+            limit,
+            next: 1,
+            in_flight: Vec::with_capacity(limit),
+            done: Vec::new(),
+            _p: PhantomData,
+        }
+    }
+
+    /// Returns the number of parts whose futures have not finished yet.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Poll the futures in flight until at most `len` of them are left.
+    fn poll_in_flight(
+        &mut self,
+        len: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Fut::Error>> {
+        let mut idx = 0;
+        while idx < self.in_flight.len() {
+            let (part_number, fut) = &mut self.in_flight[idx];
+            match fut.as_mut().try_poll(cx) {
+                Poll::Ready(res) => {
+                    let part_number = *part_number;
+                    self.in_flight.swap_remove(idx);
+                    self.done.push((part_number, res?));
+                },
+                Poll::Pending => idx += 1,
+            }
+        }
+        if self.in_flight.len() <= len {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+// The futures are pinned on the heap and nothing else is pinned.
+impl<Part, Fut: TryFuture, F> Unpin for Parallel<Part, Fut, F> {}
+
+impl<Part, Fut, F> FusedMultipartWrite<Part> for Parallel<Part, Fut, F>
+where
+    F: FnMut(usize, Part) -> Fut,
+    Fut: TryFuture,
+{
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+impl<Part, Fut, F> MultipartWrite<Part> for Parallel<Part, Fut, F>
+where
+    F: FnMut(usize, Part) -> Fut,
+    Fut: TryFuture,
+{
+    type Error = Fut::Error;
+    type Output = Vec<(usize, Fut::Ok)>;
+    type Recv = usize;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        this.poll_in_flight(this.limit - 1, cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.get_mut();
+        let part_number = this.next;
+        this.next += 1;
+        let fut = Box::pin((this.f)(part_number, part));
+        this.in_flight.push((part_number, fut));
+        Ok(part_number)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_in_flight(0, cx)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_in_flight(0, cx))?;
+        let mut done = std::mem::take(&mut this.done);
+        done.sort_unstable_by_key(|(part_number, _)| *part_number);
+        this.next = 1;
+        Poll::Ready(Ok(done))
+    }
+}
+
+impl<Part, Fut, F> AbortMultipartWrite<Part> for Parallel<Part, Fut, F>
+where
+    F: FnMut(usize, Part) -> Fut,
+    Fut: TryFuture,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // Dropping the futures that are still in flight cancels them.
+        let this = self.get_mut();
+        this.in_flight.clear();
+        this.done.clear();
+        this.next = 1;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Part, Fut, F> Debug for Parallel<Part, Fut, F>
+where
+    Fut: TryFuture,
+    Fut::Ok: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Parallel")
+            .field("limit", &self.limit)
+            .field("next", &self.next)
+            .field("in_flight", &self.in_flight.len())
+            .field("done", &self.done)
+            .finish()
+    }
+}
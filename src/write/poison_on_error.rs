@@ -0,0 +1,163 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for
+    /// [`poison_on_error`](super::MultipartWriteExt::poison_on_error).
+    #[must_use = "futures do nothing unless polled"]
+    pub struct PoisonOnError<Wr: MultipartWrite<Part>, Part> {
+        #[pin]
+        writer: Wr,
+        error: Option<Wr::Error>,
+    }
+}
+
+impl<Wr: MultipartWrite<Part>, Part> PoisonOnError<Wr, Part> {
+    pub(super) fn new(writer: Wr) -> Self {
+        Self { writer, error: None }
+    }
+
+    /// Returns `true` if the writer has returned an error.
+    pub fn is_poisoned(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Returns the first error the writer returned, if there was one.
+    pub fn error(&self) -> Option<&Wr::Error> {
+        self.error.as_ref()
+    }
+
+    /// Consumes `PoisonOnError`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+}
+
+impl<Wr, Part> FusedMultipartWrite<Part> for PoisonOnError<Wr, Part>
+where
+    Wr: MultipartWrite<Part>,
+    Wr::Error: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+impl<Wr, Part> MultipartWrite<Part> for PoisonOnError<Wr, Part>
+where
+    Wr: MultipartWrite<Part>,
+    Wr::Error: Clone,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Wr::Recv;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        if let Some(e) = this.error {
+            return Poll::Ready(Err(e.clone()));
+        }
+        let res = ready!(this.writer.poll_ready(cx));
+        Poll::Ready(poison(this.error, res))
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        if let Some(e) = this.error {
+            return Err(e.clone());
+        }
+        let res = this.writer.start_send(part);
+        poison(this.error, res)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        if let Some(e) = this.error {
+            return Poll::Ready(Err(e.clone()));
+        }
+        let res = ready!(this.writer.poll_flush(cx));
+        Poll::Ready(poison(this.error, res))
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        let this = self.project();
+        if let Some(e) = this.error {
+            return Poll::Ready(Err(e.clone()));
+        }
+        let res = ready!(this.writer.poll_complete(cx));
+        Poll::Ready(poison(this.error, res))
+    }
+}
+
+impl<Wr, Part> AbortMultipartWrite<Part> for PoisonOnError<Wr, Part>
+where
+    Wr: AbortMultipartWrite<Part>,
+    Wr::Error: Clone,
+{
+    /// Aborts the underlying writer.
+    ///
+    /// This cleans up after the error but does not make the writer usable
+    /// again, and an error from aborting does not poison the writer.
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_abort(cx)
+    }
+}
+
+impl<Wr, Part> Debug for PoisonOnError<Wr, Part>
+where
+    Wr: MultipartWrite<Part> + Debug,
+    Wr::Error: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoisonOnError")
+            .field("writer", &self.writer)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+fn poison<T, E: Clone>(
+    error: &mut Option<E>,
+    res: Result<T, E>,
+) -> Result<T, E> {
+    res.inspect_err(|e| *error = Some(e.clone()))
+}
@@ -0,0 +1,224 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::ready;
+use tokio::time::{Instant, Sleep};
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// The rate at which parts can be sent to a writer returned by
+/// [`rate_limit`](super::MultipartWriteExt::rate_limit).
+///
+/// A quota allows `amount` units every `period`, where a unit is one part, or
+/// the weight of a part when the quota is used with
+/// [`rate_limit_by`](super::MultipartWriteExt::rate_limit_by).  Up to `burst`
+/// units can be used at once, which by default is the same as `amount`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    amount: u64,
+    period: Duration,
+    burst: u64,
+}
+
+impl Quota {
+    /// Create a quota of `amount` units every `period`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is zero or `period` is zero.
+    pub fn new(amount: u64, period: Duration) -> Self {
+        assert!(amount > 0, "quota amount must be non-zero");
+        assert!(!period.is_zero(), "quota period must be non-zero");
+        Self { amount, period, burst: amount }
+    }
+
+    /// Create a quota of `amount` units per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is zero.
+    pub fn per_second(amount: u64) -> Self {
+        Self::new(amount, Duration::from_secs(1))
+    }
+
+    /// Set the number of units that can be used at once after the writer has
+    /// been idle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `burst` is zero.
+    pub fn burst(self, burst: u64) -> Self {
+        assert!(burst > 0, "quota burst must be non-zero");
+        Self { burst, ..self }
+    }
+
+    /// Returns the number of units replenished per second.
+    fn rate(&self) -> f64 {
+        self.amount as f64 / self.period.as_secs_f64()
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`rate_limit`](super::MultipartWriteExt::rate_limit)
+    /// and [`rate_limit_by`](super::MultipartWriteExt::rate_limit_by).
+    #[must_use = "futures do nothing unless polled"]
+    pub struct RateLimit<Wr, Part, F> {
+        #[pin]
+        writer: Wr,
+        quota: Quota,
+        f: F,
+        tokens: f64,
+        updated: Option<Instant>,
+        sleep: Option<Pin<Box<Sleep>>>,
+        _p: PhantomData<fn(Part)>,
+    }
+}
+
+impl<Wr, Part, F> RateLimit<Wr, Part, F> {
+    pub(super) fn new(writer: Wr, quota: Quota, f: F) -> Self {
+        Self {
+            writer,
+            quota,
+            f,
+            tokens: quota.burst as f64,
+            updated: None,
+            sleep: None,
+            _p: PhantomData,
+        }
+    }
+
+    /// Returns the quota that this writer is limited to.
+    pub fn quota(&self) -> Quota {
+        self.quota
+    }
+
+    /// Consumes `RateLimit`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Wait until there is room in the bucket for at least one more unit.
+    fn poll_capacity(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.project();
+        loop {
+            if let Some(sleep) = this.sleep.as_mut() {
+                ready!(sleep.as_mut().poll(cx));
+                *this.sleep = None;
+            }
+            let now = Instant::now();
+            let rate = this.quota.rate();
+            if let Some(updated) = *this.updated {
+                let elapsed = now.saturating_duration_since(updated);
+                *this.tokens = (*this.tokens + elapsed.as_secs_f64() * rate)
+                    .min(this.quota.burst as f64);
+            }
+            *this.updated = Some(now);
+            if *this.tokens >= 1.0 {
+                return Poll::Ready(());
+            }
+            let wait = Duration::from_secs_f64((1.0 - *this.tokens) / rate);
+            *this.sleep = Some(Box::pin(tokio::time::sleep_until(now + wait)));
+        }
+    }
+}
+
+impl<Wr, Part, F> FusedMultipartWrite<Part> for RateLimit<Wr, Part, F>
+where
+    Wr: FusedMultipartWrite<Part>,
+    F: FnMut(&Part) -> u64,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr, Part, F> MultipartWrite<Part> for RateLimit<Wr, Part, F>
+where
+    Wr: MultipartWrite<Part>,
+    F: FnMut(&Part) -> u64,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Wr::Recv;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_capacity(cx));
+        self.project().writer.poll_ready(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        // The bucket can go into deficit for a heavy part, in which case the
+        // writer is not ready again until the deficit has been paid back.
+        *this.tokens -= (this.f)(&part) as f64;
+        this.writer.start_send(part)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_flush(cx)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        self.project().writer.poll_complete(cx)
+    }
+}
+
+impl<Wr, Part, F> AbortMultipartWrite<Part> for RateLimit<Wr, Part, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(&Part) -> u64,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // Cleaning up is not subject to the quota.
+        self.project().writer.poll_abort(cx)
+    }
+}
+
+impl<Wr: Debug, Part, F> Debug for RateLimit<Wr, Part, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimit")
+            .field("writer", &self.writer)
+            .field("quota", &self.quota)
+            .field("tokens", &self.tokens)
+            .field("updated", &self.updated)
+            .field("sleep", &self.sleep)
+            .finish()
+    }
+}
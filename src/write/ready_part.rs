@@ -5,7 +5,7 @@ use std::task::{Context, Poll};
 
 use futures_core::ready;
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`ready_part`].
@@ -139,6 +139,24 @@ where
     }
 }
 
+impl<Wr, Part, P, Fut, F> AbortMultipartWrite<P>
+    for ReadyPart<Wr, Part, P, Fut, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(P) -> Fut,
+    Fut: Future<Output = Result<Part, Wr::Error>>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+        this.future.set(None);
+        *this.buffered = None;
+        this.writer.poll_abort(cx)
+    }
+}
+
 impl<Wr, Part, P, Fut, F> Debug for ReadyPart<Wr, Part, P, Fut, F>
 where
     Wr: Debug,
@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// A future in flight, or the part that it resolved to.
+enum Slot<Fut, Part> {
+    Pending(Pin<Box<Fut>>),
+    Done(Part),
+}
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`ready_part_concurrent`] and
+    /// [`ready_part_unordered`].
+    ///
+    /// [`ready_part_concurrent`]: super::MultipartWriteExt::ready_part_concurrent
+    /// [`ready_part_unordered`]: super::MultipartWriteExt::ready_part_unordered
+    #[must_use = "futures do nothing unless polled"]
+    pub struct ReadyPartConcurrent<Wr, Part, P, Fut, F> {
+        #[pin]
+        writer: Wr,
+        f: F,
+        limit: usize,
+        ordered: bool,
+        queue: VecDeque<Slot<Fut, Part>>,
+        _p: PhantomData<fn(P) -> Part>,
+    }
+}
+
+impl<Wr, Part, P, Fut, F> ReadyPartConcurrent<Wr, Part, P, Fut, F> {
+    pub(super) fn new(writer: Wr, limit: usize, ordered: bool, f: F) -> Self {
+        assert!(limit > 0, "concurrency limit must be non-zero");
+        Self {
+            writer,
+            f,
+            limit,
+            ordered,
+            queue: VecDeque::with_capacity(limit),
+            _p: PhantomData,
+        }
+    }
+
+    /// Returns the number of parts that are being prepared or are waiting to
+    /// be sent to the underlying writer.
+    pub fn in_flight(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Consumes `ReadyPartConcurrent`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Make progress on the futures in flight and send the parts they have
+    /// resolved to, until at most `len` of them are left.
+    fn poll_queue(
+        self: Pin<&mut Self>,
+        len: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Wr::Error>>
+    where
+        Wr: MultipartWrite<Part>,
+        Fut: Future<Output = Result<Part, Wr::Error>>,
+    {
+        let mut this = self.project();
+
+        let mut idx = 0;
+        while idx < this.queue.len() {
+            if let Slot::Pending(fut) = &mut this.queue[idx] {
+                if let Poll::Ready(res) = fut.as_mut().poll(cx) {
+                    match res {
+                        Ok(part) => this.queue[idx] = Slot::Done(part),
+                        // The finished future must not be polled again.
+                        Err(e) => {
+                            this.queue.remove(idx);
+                            return Poll::Ready(Err(e));
+                        },
+                    }
+                }
+            }
+            idx += 1;
+        }
+
+        loop {
+            let next = if *this.ordered {
+                this.queue.front().and_then(|slot| match slot {
+                    Slot::Done(_) => Some(0),
+                    Slot::Pending(_) => None,
+                })
+            } else {
+                this.queue.iter().position(|slot| matches!(slot, Slot::Done(_)))
+            };
+            let Some(idx) = next else {
+                break;
+            };
+            ready!(this.writer.as_mut().poll_ready(cx))?;
+            if let Some(Slot::Done(part)) = this.queue.remove(idx) {
+                this.writer.as_mut().start_send(part)?;
+            }
+        }
+
+        // Otherwise one of the futures in flight is pending.
+        if this.queue.len() <= len {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<Wr, Part, P, Fut, F> FusedMultipartWrite<P>
+    for ReadyPartConcurrent<Wr, Part, P, Fut, F>
+where
+    Wr: FusedMultipartWrite<Part>,
+    F: FnMut(P) -> Fut,
+    Fut: Future<Output = Result<Part, Wr::Error>>,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr, Part, P, Fut, F> MultipartWrite<P>
+    for ReadyPartConcurrent<Wr, Part, P, Fut, F>
+where
+    Wr: MultipartWrite<Part>,
+    F: FnMut(P) -> Fut,
+    Fut: Future<Output = Result<Part, Wr::Error>>,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = ();
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let len = self.limit - 1;
+        self.poll_queue(len, cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: P,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        let fut = Box::pin((this.f)(part));
+        this.queue.push_back(Slot::Pending(fut));
+        Ok(())
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_queue(0, cx))?;
+        self.project().writer.poll_flush(cx)
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        ready!(self.as_mut().poll_queue(0, cx))?;
+        self.project().writer.poll_complete(cx)
+    }
+}
+
+impl<Wr, Part, P, Fut, F> AbortMultipartWrite<P>
+    for ReadyPartConcurrent<Wr, Part, P, Fut, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(P) -> Fut,
+    Fut: Future<Output = Result<Part, Wr::Error>>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        // Dropping the futures that are still running cancels them.
+        this.queue.clear();
+        this.writer.poll_abort(cx)
+    }
+}
+
+impl<Wr, Part, P, Fut, F> Debug for ReadyPartConcurrent<Wr, Part, P, Fut, F>
+where
+    Wr: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadyPartConcurrent")
+            .field("writer", &self.writer)
+            .field("limit", &self.limit)
+            .field("ordered", &self.ordered)
+            .field("in_flight", &self.queue.len())
+            .finish()
+    }
+}
@@ -0,0 +1,429 @@
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, RandomState};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::ready;
+use tokio::time::Sleep;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// Decides whether an operation that failed with an error should be retried,
+/// and how long to wait before retrying it.
+///
+/// This is implemented for closures `FnMut(&E, usize) -> Option<Duration>`.
+pub trait RetryPolicy<E> {
+    /// Returns the time to wait before retrying after `error`, or `None` if
+    /// the error should be returned to the caller.
+    ///
+    /// `attempt` is the number of consecutive failures so far, starting at 1.
+    fn retry(&mut self, error: &E, attempt: usize) -> Option<Duration>;
+
+    /// Returns `true` if errors from completing the writer should be retried
+    /// as well.
+    ///
+    /// Completing a writer again after it failed is only safe for writers
+    /// that allow it, so this is `false` by default.
+    fn retries_complete(&self) -> bool {
+        false
+    }
+}
+
+impl<E, F> RetryPolicy<E> for F
+where
+    F: FnMut(&E, usize) -> Option<Duration>,
+{
+    fn retry(&mut self, error: &E, attempt: usize) -> Option<Duration> {
+        (self)(error, attempt)
+    }
+}
+
+/// A [`RetryPolicy`] that retries every error a bounded number of times,
+/// waiting exponentially longer between attempts.
+///
+/// By default the delay doubles after each attempt, up to a maximum delay of
+/// 30 seconds, at most 3 retries are attempted, and errors from completing
+/// the writer are not retried.
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialBackoff {
+    initial: Duration,
+    factor: u32,
+    max_delay: Duration,
+    max_retries: usize,
+    jitter: bool,
+    complete: bool,
+}
+
+impl ExponentialBackoff {
+    /// Create a new policy that waits `initial` before the first retry.
+    pub fn new(initial: Duration) -> Self {
+        Self {
+            initial,
+            factor: 2,
+            max_delay: Duration::from_secs(30),
+            max_retries: 3,
+            jitter: false,
+            complete: false,
+        }
+    }
+
+    /// Set the factor by which the delay increases after each attempt.
+    pub fn factor(self, factor: u32) -> Self {
+        Self { factor, ..self }
+    }
+
+    /// Set the maximum time to wait between attempts.
+    pub fn max_delay(self, max_delay: Duration) -> Self {
+        Self { max_delay, ..self }
+    }
+
+    /// Set the maximum number of times to retry before returning the error.
+    pub fn max_retries(self, max_retries: usize) -> Self {
+        Self { max_retries, ..self }
+    }
+
+    /// Wait a random duration between zero and the computed delay, which
+    /// spreads out the retries of many writers that failed at the same time.
+    ///
+    /// The duration is uniformly distributed, using the randomly seeded
+    /// hasher of the standard library as the source of randomness.  It is
+    /// not cryptographically secure, which does not matter for this.
+    pub fn jitter(self) -> Self {
+        Self { jitter: true, ..self }
+    }
+
+    /// Retry errors from completing the writer as well.
+    ///
+    /// See [`RetryPolicy::retries_complete`].
+    pub fn retry_complete(self) -> Self {
+        Self { complete: true, ..self }
+    }
+
+    /// Only retry the errors for which `f` returns `true`.
+    pub fn retry_if<F>(self, f: F) -> RetryIf<Self, F> {
+        RetryIf { policy: self, f }
+    }
+
+    fn delay(&self, attempt: usize) -> Duration {
+        let exp = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        let delay = self
+            .factor
+            .checked_pow(exp)
+            .and_then(|n| self.initial.checked_mul(n))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+        if !self.jitter {
+            return delay;
+        }
+        // Every `RandomState` has different keys, so hashing with a new one
+        // gives a uniformly distributed number.
+        let n = RandomState::new().hash_one(attempt);
+        delay.mul_f64(n as f64 / u64::MAX as f64)
+    }
+}
+
+impl<E> RetryPolicy<E> for ExponentialBackoff {
+    fn retry(&mut self, _: &E, attempt: usize) -> Option<Duration> {
+        (attempt <= self.max_retries).then(|| self.delay(attempt))
+    }
+
+    fn retries_complete(&self) -> bool {
+        self.complete
+    }
+}
+
+/// A [`RetryPolicy`] that only retries errors satisfying a predicate.
+///
+/// Returned by [`ExponentialBackoff::retry_if`].
+#[derive(Clone, Copy)]
+pub struct RetryIf<P, F> {
+    policy: P,
+    f: F,
+}
+
+impl<E, P, F> RetryPolicy<E> for RetryIf<P, F>
+where
+    P: RetryPolicy<E>,
+    F: FnMut(&E) -> bool,
+{
+    fn retry(&mut self, error: &E, attempt: usize) -> Option<Duration> {
+        if !(self.f)(error) {
+            return None;
+        }
+        self.policy.retry(error, attempt)
+    }
+
+    fn retries_complete(&self) -> bool {
+        self.policy.retries_complete()
+    }
+}
+
+impl<P: Debug, F> Debug for RetryIf<P, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryIf").field("policy", &self.policy).finish()
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`retry`](super::MultipartWriteExt::retry).
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Retry<Wr: MultipartWrite<Part>, Part, P> {
+        #[pin]
+        writer: Wr,
+        policy: P,
+        sleep: Option<Pin<Box<Sleep>>>,
+        attempt: usize,
+        buffered: Option<Part>,
+        recv: Option<Wr::Recv>,
+        _p: PhantomData<fn(Part)>,
+    }
+}
+
+impl<Wr: MultipartWrite<Part>, Part, P> Retry<Wr, Part, P> {
+    pub(super) fn new(writer: Wr, policy: P) -> Self {
+        Self {
+            writer,
+            policy,
+            sleep: None,
+            attempt: 0,
+            buffered: None,
+            recv: None,
+            _p: PhantomData,
+        }
+    }
+
+    /// Takes the value returned by the inner writer for a part that failed to
+    /// be sent the first time, once it was sent successfully.
+    ///
+    /// Sending that part returned `None`, since it was only sent again the
+    /// next time the writer was polled.
+    pub fn take_recv(self: Pin<&mut Self>) -> Option<Wr::Recv> {
+        self.project().recv.take()
+    }
+
+    /// Consumes `Retry`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Consult the policy about the error, scheduling the next attempt if it
+    /// should be retried and returning it otherwise.
+    fn backoff<E>(self: Pin<&mut Self>, error: E) -> Result<(), E>
+    where
+        P: RetryPolicy<E>,
+    {
+        let this = self.project();
+        *this.attempt += 1;
+        match this.policy.retry(&error, *this.attempt) {
+            Some(delay) => {
+                *this.sleep = Some(Box::pin(tokio::time::sleep(delay)));
+                Ok(())
+            },
+            _ => {
+                *this.attempt = 0;
+                *this.buffered = None;
+                Err(error)
+            },
+        }
+    }
+
+    /// Wait out the current backoff and then send a part that previously
+    /// failed to be sent.
+    fn poll_retry(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Wr::Error>>
+    where
+        Part: Clone,
+        P: RetryPolicy<Wr::Error>,
+    {
+        loop {
+            let mut this = self.as_mut().project();
+            if let Some(sleep) = this.sleep.as_mut() {
+                ready!(sleep.as_mut().poll(cx));
+                *this.sleep = None;
+            }
+            let Some(part) = this.buffered.take() else {
+                return Poll::Ready(Ok(()));
+            };
+            let res = match this.writer.as_mut().poll_ready(cx) {
+                Poll::Ready(Ok(())) => {
+                    this.writer.as_mut().start_send(part.clone())
+                },
+                Poll::Ready(Err(e)) => Err(e),
+                Poll::Pending => {
+                    *this.buffered = Some(part);
+                    return Poll::Pending;
+                },
+            };
+            match res {
+                Ok(recv) => {
+                    *this.attempt = 0;
+                    *this.recv = Some(recv);
+                },
+                Err(e) => {
+                    *this.buffered = Some(part);
+                    self.as_mut().backoff(e)?;
+                },
+            }
+        }
+    }
+}
+
+impl<Wr, Part, P> FusedMultipartWrite<Part> for Retry<Wr, Part, P>
+where
+    Part: Clone,
+    Wr: FusedMultipartWrite<Part>,
+    P: RetryPolicy<Wr::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr, Part, P> MultipartWrite<Part> for Retry<Wr, Part, P>
+where
+    Part: Clone,
+    Wr: MultipartWrite<Part>,
+    P: RetryPolicy<Wr::Error>,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Option<Wr::Recv>;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        loop {
+            ready!(self.as_mut().poll_retry(cx))?;
+            let mut this = self.as_mut().project();
+            match ready!(this.writer.as_mut().poll_ready(cx)) {
+                Ok(()) => {
+                    *this.attempt = 0;
+                    return Poll::Ready(Ok(()));
+                },
+                Err(e) => self.as_mut().backoff(e)?,
+            }
+        }
+    }
+
+    fn start_send(
+        mut self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.as_mut().project();
+        match this.writer.start_send(part.clone()) {
+            Ok(recv) => {
+                *this.attempt = 0;
+                Ok(Some(recv))
+            },
+            Err(e) => {
+                // Keep the part to send it again when the writer is next
+                // polled.
+                *this.buffered = Some(part);
+                self.backoff(e)?;
+                Ok(None)
+            },
+        }
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        loop {
+            ready!(self.as_mut().poll_retry(cx))?;
+            let mut this = self.as_mut().project();
+            match ready!(this.writer.as_mut().poll_flush(cx)) {
+                Ok(()) => {
+                    *this.attempt = 0;
+                    return Poll::Ready(Ok(()));
+                },
+                Err(e) => self.as_mut().backoff(e)?,
+            }
+        }
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        loop {
+            ready!(self.as_mut().poll_retry(cx))?;
+            let mut this = self.as_mut().project();
+            match ready!(this.writer.as_mut().poll_complete(cx)) {
+                Ok(out) => {
+                    *this.attempt = 0;
+                    return Poll::Ready(Ok(out));
+                },
+                Err(e) if this.policy.retries_complete() => {
+                    self.as_mut().backoff(e)?;
+                },
+                Err(e) => {
+                    *this.attempt = 0;
+                    return Poll::Ready(Err(e));
+                },
+            }
+        }
+    }
+}
+
+impl<Wr, Part, P> AbortMultipartWrite<Part> for Retry<Wr, Part, P>
+where
+    Part: Clone,
+    Wr: AbortMultipartWrite<Part>,
+    P: RetryPolicy<Wr::Error>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        // Stop retrying the part that failed, since it would only be written
+        // to be thrown away.
+        *this.sleep = None;
+        *this.attempt = 0;
+        *this.buffered = None;
+        this.writer.poll_abort(cx)
+    }
+}
+
+impl<Wr, Part, P> Debug for Retry<Wr, Part, P>
+where
+    Wr: MultipartWrite<Part> + Debug,
+    Wr::Recv: Debug,
+    Part: Debug,
+    P: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field("writer", &self.writer)
+            .field("policy", &self.policy)
+            .field("sleep", &self.sleep)
+            .field("attempt", &self.attempt)
+            .field("buffered", &self.buffered)
+            .field("recv", &self.recv)
+            .finish()
+    }
+}
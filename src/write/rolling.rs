@@ -0,0 +1,354 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::ready;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// Returns a `MultipartWrite` that completes its writer and replaces it with a
+/// new one from `make` whenever the [`RollPolicy`] says to.
+///
+/// Each writer is a generation, numbered from 0, and the value returned by
+/// sending a part is the generation that it was sent to together with the
+/// value that writer returned.  The policy is checked before a part is sent,
+/// so a writer is only rolled over when there is another part for the next
+/// one.  Completing the writer completes the current generation and returns
+/// the outputs of all generations in order, after which the generations
+/// start again from 0.
+///
+/// A writer is only made by `make` when there is a part to send to it, so
+/// rolling over or completing does not leave an unused writer behind.  The
+/// exception is completing a write that had nothing sent to it, which makes
+/// a writer to complete so that there is always at least one output.
+///
+/// Aborting the writer aborts the current generation.  Generations that were
+/// already completed cannot be aborted, and their outputs can be taken with
+/// [`Rolling::take_committed`].
+///
+/// Parts are counted as having no size, so [`RollPolicy::max_bytes`] has no
+/// effect.  Use [`rolling_by`] to give parts a size.
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use multipart_write::write::RollPolicy;
+/// use multipart_write::{MultipartWriteExt as _, write};
+///
+/// let policy = RollPolicy::new().max_parts(2);
+/// let mut writer = write::rolling(Vec::new, policy);
+///
+/// let (g1, _) = writer.send_flush(1).await.unwrap();
+/// let (g2, _) = writer.send_flush(2).await.unwrap();
+/// let (g3, _) = writer.send_flush(3).await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!((g1, g2, g3), (0, 0, 1));
+/// assert_eq!(out, vec![vec![1, 2], vec![3]]);
+/// # })
+/// ```
+pub fn rolling<Wr, Part, M>(
+    make: M,
+    policy: RollPolicy,
+) -> Rolling<Wr, Part, M, fn(&Part) -> u64>
+where
+    Wr: MultipartWrite<Part>,
+    M: FnMut() -> Wr,
+{
+    Rolling::new(make, policy, |_| 0)
+}
+
+/// Returns a `MultipartWrite` that completes its writer and replaces it with a
+/// new one from `make` whenever the [`RollPolicy`] says to, where the size of
+/// each part is the number of bytes returned by `f`.
+///
+/// This is the same as [`rolling`] except that [`RollPolicy::max_bytes`]
+/// applies to the sizes returned by `f`.
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use multipart_write::write::RollPolicy;
+/// use multipart_write::{MultipartWriteExt as _, write};
+///
+/// let policy = RollPolicy::new().max_bytes(4);
+/// let mut writer = write::rolling_by(
+///     || write::extend(String::new()),
+///     policy,
+///     |s: &&str| s.len() as u64,
+/// );
+///
+/// writer.send_flush("abc").await.unwrap();
+/// writer.send_flush("de").await.unwrap();
+/// writer.send_flush("f").await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!(out, vec!["abcde".to_string(), "f".to_string()]);
+/// # })
+/// ```
+pub fn rolling_by<Wr, Part, M, F>(
+    make: M,
+    policy: RollPolicy,
+    f: F,
+) -> Rolling<Wr, Part, M, F>
+where
+    Wr: MultipartWrite<Part>,
+    M: FnMut() -> Wr,
+    F: FnMut(&Part) -> u64,
+{
+    Rolling::new(make, policy, f)
+}
+
+/// When a writer returned by [`rolling`] starts a new generation.
+///
+/// A generation is rolled over when any one of the limits that were set has
+/// been reached.  The age of a generation is measured from the time that its
+/// first part was sent, and like the other limits it is only checked when
+/// another part is about to be sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RollPolicy {
+    max_parts: Option<usize>,
+    max_bytes: Option<u64>,
+    max_age: Option<Duration>,
+}
+
+impl RollPolicy {
+    /// Create a new policy with no limits, which never rolls over.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Roll over after this many parts have been sent to a generation.
+    pub fn max_parts(self, max_parts: usize) -> Self {
+        Self { max_parts: Some(max_parts), ..self }
+    }
+
+    /// Roll over after this many bytes have been sent to a generation.
+    pub fn max_bytes(self, max_bytes: u64) -> Self {
+        Self { max_bytes: Some(max_bytes), ..self }
+    }
+
+    /// Roll over once a generation is this old.
+    pub fn max_age(self, max_age: Duration) -> Self {
+        Self { max_age: Some(max_age), ..self }
+    }
+
+    fn should_roll(&self, parts: usize, bytes: u64, started: Instant) -> bool {
+        self.max_parts.is_some_and(|n| parts >= n)
+            || self.max_bytes.is_some_and(|n| bytes >= n)
+            || self.max_age.is_some_and(|age| started.elapsed() >= age)
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`rolling`] and [`rolling_by`].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Rolling<Wr: MultipartWrite<Part>, Part, M, F> {
+        #[pin]
+        writer: Option<Wr>,
+        make: M,
+        f: F,
+        policy: RollPolicy,
+        generation: usize,
+        parts: usize,
+        bytes: u64,
+        started: Option<Instant>,
+        outputs: Vec<Wr::Output>,
+        committed: Vec<Wr::Output>,
+    }
+}
+
+impl<Wr, Part, M, F> Rolling<Wr, Part, M, F>
+where
+    Wr: MultipartWrite<Part>,
+    M: FnMut() -> Wr,
+{
+    fn new(make: M, policy: RollPolicy, f: F) -> Self {
+        Self {
+            writer: None,
+            make,
+            f,
+            policy,
+            generation: 0,
+            parts: 0,
+            bytes: 0,
+            started: None,
+            outputs: Vec::new(),
+            committed: Vec::new(),
+        }
+    }
+
+    /// Returns the number of the current generation.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Takes the outputs of the generations that had already been completed
+    /// when the writer was aborted.
+    ///
+    /// A completed generation cannot be aborted, so these are what the
+    /// aborted writes really wrote, in order.
+    pub fn take_committed(self: Pin<&mut Self>) -> Vec<Wr::Output> {
+        std::mem::take(self.project().committed)
+    }
+
+    /// Acquires a reference to the writer of the current generation, if it
+    /// has been made.
+    pub fn get_ref(&self) -> Option<&Wr> {
+        self.writer.as_ref()
+    }
+
+    /// Acquires a pinned mutable reference to the writer of the current
+    /// generation, if it has been made.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Option<Pin<&mut Wr>> {
+        self.project().writer.as_pin_mut()
+    }
+
+    /// Complete the current generation.  The writer of the next one is only
+    /// made when there is a part for it.
+    fn poll_roll(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Wr::Error>> {
+        let mut this = self.project();
+        if this.writer.is_none() {
+            this.writer.set(Some((this.make)()));
+        }
+        let writer = this.writer.as_mut().as_pin_mut().unwrap();
+        let out = ready!(writer.poll_complete(cx))?;
+        this.outputs.push(out);
+        this.writer.set(None);
+        *this.generation += 1;
+        *this.parts = 0;
+        *this.bytes = 0;
+        *this.started = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Wr, Part, M, F> FusedMultipartWrite<Part> for Rolling<Wr, Part, M, F>
+where
+    Wr: FusedMultipartWrite<Part>,
+    M: FnMut() -> Wr,
+    F: FnMut(&Part) -> u64,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.as_ref().is_some_and(|wr| wr.is_terminated())
+    }
+}
+
+impl<Wr, Part, M, F> MultipartWrite<Part> for Rolling<Wr, Part, M, F>
+where
+    Wr: MultipartWrite<Part>,
+    M: FnMut() -> Wr,
+    F: FnMut(&Part) -> u64,
+{
+    type Error = Wr::Error;
+    type Output = Vec<Wr::Output>;
+    type Recv = (usize, Wr::Recv);
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        if let Some(started) = self.started {
+            if self.policy.should_roll(self.parts, self.bytes, started) {
+                ready!(self.as_mut().poll_roll(cx))?;
+            }
+        }
+        let mut this = self.project();
+        if this.writer.is_none() {
+            this.writer.set(Some((this.make)()));
+        }
+        this.writer.as_pin_mut().unwrap().poll_ready(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        let size = (this.f)(&part);
+        let writer = this.writer.as_pin_mut().expect("writer is not ready");
+        let recv = writer.start_send(part)?;
+        *this.parts += 1;
+        *this.bytes += size;
+        this.started.get_or_insert_with(Instant::now);
+        Ok((*this.generation, recv))
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        match self.project().writer.as_pin_mut() {
+            Some(writer) => writer.poll_flush(cx),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        // There is no writer after rolling over until the next part, but a
+        // write always has at least one generation.
+        if self.writer.is_some() || self.outputs.is_empty() {
+            ready!(self.as_mut().poll_roll(cx))?;
+        }
+        let this = self.project();
+        *this.generation = 0;
+        Poll::Ready(Ok(std::mem::take(this.outputs)))
+    }
+}
+
+impl<Wr, Part, M, F> AbortMultipartWrite<Part> for Rolling<Wr, Part, M, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    M: FnMut() -> Wr,
+    F: FnMut(&Part) -> u64,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+        let res = match this.writer.as_mut().as_pin_mut() {
+            Some(writer) => ready!(writer.poll_abort(cx)),
+            _ => Ok(()),
+        };
+        this.writer.set(None);
+        // Generations that were already completed cannot be aborted, so their
+        // outputs are kept to be taken with `take_committed`.
+        this.committed.append(this.outputs);
+        *this.generation = 0;
+        *this.parts = 0;
+        *this.bytes = 0;
+        *this.started = None;
+        Poll::Ready(res)
+    }
+}
+
+impl<Wr, Part, M, F> Debug for Rolling<Wr, Part, M, F>
+where
+    Wr: MultipartWrite<Part> + Debug,
+    Wr::Output: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rolling")
+            .field("writer", &self.writer)
+            .field("policy", &self.policy)
+            .field("generation", &self.generation)
+            .field("parts", &self.parts)
+            .field("bytes", &self.bytes)
+            .field("started", &self.started)
+            .field("outputs", &self.outputs)
+            .field("committed", &self.committed)
+            .finish()
+    }
+}
@@ -0,0 +1,235 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use super::fanout::join;
+use crate::{AbortMultipartWrite, Either, FusedMultipartWrite, MultipartWrite};
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`route`](super::MultipartWriteExt::route).
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Route<Wr1: MultipartWrite<Part>, Wr2: MultipartWrite<Part>, Part, F> {
+        #[pin]
+        wr1: Wr1,
+        #[pin]
+        wr2: Wr2,
+        f: F,
+        ready1: bool,
+        ready2: bool,
+        wro1: Option<Wr1::Output>,
+        wro2: Option<Wr2::Output>,
+        _p: PhantomData<fn(Part)>,
+    }
+}
+
+impl<Wr1, Wr2, Part, F> Route<Wr1, Wr2, Part, F>
+where
+    Wr1: MultipartWrite<Part>,
+    Wr2: MultipartWrite<Part>,
+{
+    pub(super) fn new(wr1: Wr1, wr2: Wr2, f: F) -> Self {
+        Self {
+            wr1,
+            wr2,
+            f,
+            ready1: false,
+            ready2: false,
+            wro1: None,
+            wro2: None,
+            _p: PhantomData,
+        }
+    }
+
+    /// Consumes `Route`, returning the underlying writers.
+    pub fn into_inner(self) -> (Wr1, Wr2) {
+        (self.wr1, self.wr2)
+    }
+
+    /// Acquires a reference to the underlying writers.
+    pub fn get_ref(&self) -> (&Wr1, &Wr2) {
+        (&self.wr1, &self.wr2)
+    }
+
+    /// Acquires a mutable reference to the underlying writers.
+    ///
+    /// It is inadvisable to directly write to the underlying writers.
+    pub fn get_mut(&mut self) -> (&mut Wr1, &mut Wr2) {
+        (&mut self.wr1, &mut self.wr2)
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writers.
+    ///
+    /// It is inadvisable to directly write to the underlying writers.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> (Pin<&mut Wr1>, Pin<&mut Wr2>) {
+        let this = self.project();
+        (this.wr1, this.wr2)
+    }
+
+    /// Poll the writer that `part` would be routed to for readiness.
+    ///
+    /// Unlike `poll_ready`, this does not wait on the other writer, so when
+    /// it returns `Poll::Ready(Ok(()))` the same part can be sent right away
+    /// even if the other writer is not ready.  `f` is called again when the
+    /// part is sent, and must give the same result.
+    pub fn poll_ready_part(
+        self: Pin<&mut Self>,
+        part: &Part,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Wr1::Error>>
+    where
+        Wr2: MultipartWrite<Part, Error = Wr1::Error>,
+        F: FnMut(&Part) -> bool,
+    {
+        let this = self.project();
+        if (this.f)(part) {
+            if !*this.ready1 {
+                ready!(this.wr1.poll_ready(cx))?;
+                *this.ready1 = true;
+            }
+        } else if !*this.ready2 {
+            ready!(this.wr2.poll_ready(cx))?;
+            *this.ready2 = true;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Wr1, Wr2, Part, F> FusedMultipartWrite<Part> for Route<Wr1, Wr2, Part, F>
+where
+    Wr1: FusedMultipartWrite<Part>,
+    Wr2: FusedMultipartWrite<Part, Error = Wr1::Error>,
+    F: FnMut(&Part) -> bool,
+{
+    fn is_terminated(&self) -> bool {
+        self.wr1.is_terminated() || self.wr2.is_terminated()
+    }
+}
+
+impl<Wr1, Wr2, Part, F> MultipartWrite<Part> for Route<Wr1, Wr2, Part, F>
+where
+    Wr1: MultipartWrite<Part>,
+    Wr2: MultipartWrite<Part, Error = Wr1::Error>,
+    F: FnMut(&Part) -> bool,
+{
+    type Error = Wr1::Error;
+    type Output = (Wr1::Output, Wr2::Output);
+    type Recv = Either<Wr1::Recv, Wr2::Recv>;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        // Which writer the next part goes to is not known yet, so both have
+        // to be ready.  A writer that is ready stays ready until it is sent a
+        // part, so it is not polled again in the meantime.
+        if !*this.ready1 && this.wr1.poll_ready(cx)?.is_ready() {
+            *this.ready1 = true;
+        }
+        if !*this.ready2 && this.wr2.poll_ready(cx)?.is_ready() {
+            *this.ready2 = true;
+        }
+        if *this.ready1 && *this.ready2 {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        if (this.f)(&part) {
+            *this.ready1 = false;
+            this.wr1.start_send(part).map(Either::Left)
+        } else {
+            *this.ready2 = false;
+            this.wr2.start_send(part).map(Either::Right)
+        }
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        let res1 = this.wr1.poll_flush(cx);
+        let res2 = this.wr2.poll_flush(cx);
+        join(res1, res2)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        let this = self.project();
+        let res1 = match this.wro1 {
+            Some(_) => Poll::Ready(Ok(())),
+            None => this.wr1.poll_complete(cx).map_ok(|out| {
+                *this.wro1 = Some(out);
+            }),
+        };
+        let res2 = match this.wro2 {
+            Some(_) => Poll::Ready(Ok(())),
+            None => this.wr2.poll_complete(cx).map_ok(|out| {
+                *this.wro2 = Some(out);
+            }),
+        };
+        // Readiness has to be checked again for a completed writer.
+        *this.ready1 = false;
+        *this.ready2 = false;
+        if let Err(e) = ready!(join(res1, res2)) {
+            *this.wro1 = None;
+            *this.wro2 = None;
+            return Poll::Ready(Err(e));
+        }
+        let out1 = this.wro1.take().unwrap();
+        let out2 = this.wro2.take().unwrap();
+        Poll::Ready(Ok((out1, out2)))
+    }
+}
+
+impl<Wr1, Wr2, Part, F> AbortMultipartWrite<Part> for Route<Wr1, Wr2, Part, F>
+where
+    Wr1: AbortMultipartWrite<Part>,
+    Wr2: AbortMultipartWrite<Part, Error = Wr1::Error>,
+    F: FnMut(&Part) -> bool,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        *this.ready1 = false;
+        *this.ready2 = false;
+        *this.wro1 = None;
+        *this.wro2 = None;
+        let res1 = this.wr1.poll_abort(cx);
+        let res2 = this.wr2.poll_abort(cx);
+        join(res1, res2)
+    }
+}
+
+impl<Wr1, Wr2, Part, F> Debug for Route<Wr1, Wr2, Part, F>
+where
+    Wr1: MultipartWrite<Part> + Debug,
+    Wr2: MultipartWrite<Part> + Debug,
+    Wr1::Output: Debug,
+    Wr2::Output: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Route")
+            .field("wr1", &self.wr1)
+            .field("wr2", &self.wr2)
+            .field("ready1", &self.ready1)
+            .field("ready2", &self.ready2)
+            .field("wro1", &self.wro1)
+            .field("wro2", &self.wro2)
+            .finish()
+    }
+}
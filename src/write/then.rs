@@ -5,7 +5,7 @@ use std::task::{Context, Poll};
 
 use futures_core::ready;
 
-use crate::{FusedMultipartWrite, MultipartWrite};
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
 
 pin_project_lite::pin_project! {
     /// `MultipartWrite` for [`then`](super::MultipartWriteExt::then).
@@ -114,6 +114,23 @@ where
     }
 }
 
+impl<Wr, Part, T, Fut, F> AbortMultipartWrite<Part>
+    for Then<Wr, Part, T, Fut, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(Result<Wr::Output, Wr::Error>) -> Fut,
+    Fut: Future<Output = Result<T, Wr::Error>>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let mut this = self.project();
+        this.fut.set(None);
+        this.writer.poll_abort(cx)
+    }
+}
+
 impl<Wr, Part, T, Fut, F> Debug for Then<Wr, Part, T, Fut, F>
 where
     Wr: Debug,
@@ -0,0 +1,302 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::time::Sleep;
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// Limits on the time a writer can spend in each phase of a write.
+///
+/// A limit that is not set means the phase can take as long as it needs to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeoutConfig {
+    ready: Option<Duration>,
+    flush: Option<Duration>,
+    complete: Option<Duration>,
+    deadline: Option<Duration>,
+}
+
+impl TimeoutConfig {
+    /// Create a new configuration with no limits set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum time `poll_ready` can take to become ready.
+    pub fn ready(self, limit: Duration) -> Self {
+        Self { ready: Some(limit), ..self }
+    }
+
+    /// Set the maximum time `poll_flush` can take to finish flushing.
+    pub fn flush(self, limit: Duration) -> Self {
+        Self { flush: Some(limit), ..self }
+    }
+
+    /// Set the maximum time `poll_complete` can take to produce the output.
+    pub fn complete(self, limit: Duration) -> Self {
+        Self { complete: Some(limit), ..self }
+    }
+
+    /// Set the maximum time for the entire write, starting from the first time
+    /// the writer is polled and ending when it is completed.
+    ///
+    /// Once the deadline has passed, every call fails with
+    /// [`Phase::Deadline`] until the writer is completed or aborted, which
+    /// ends the write.
+    pub fn deadline(self, limit: Duration) -> Self {
+        Self { deadline: Some(limit), ..self }
+    }
+
+    fn limit(&self, phase: Phase) -> Option<Duration> {
+        match phase {
+            Phase::Ready => self.ready,
+            Phase::Flush => self.flush,
+            Phase::Complete => self.complete,
+            Phase::Deadline => self.deadline,
+        }
+    }
+}
+
+/// The phase of a write that took too long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// `poll_ready` did not become ready.
+    Ready,
+    /// `poll_flush` did not finish flushing.
+    Flush,
+    /// `poll_complete` did not produce the output.
+    Complete,
+    /// The entire write was not completed.
+    Deadline,
+}
+
+/// Error for [`timeout`](super::MultipartWriteExt::timeout) returned when a
+/// phase of the write exceeded its time limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Elapsed {
+    phase: Phase,
+    limit: Duration,
+}
+
+impl Elapsed {
+    fn new(phase: Phase, limit: Duration) -> Self {
+        Self { phase, limit }
+    }
+
+    /// Returns the phase of the write that took too long.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Returns the limit that was exceeded.
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+}
+
+impl Display for Elapsed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let phase = match self.phase {
+            Phase::Ready => "ready",
+            Phase::Flush => "flush",
+            Phase::Complete => "complete",
+            Phase::Deadline => "write deadline",
+        };
+        write!(f, "{phase} timeout of {:?} elapsed", self.limit)
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+impl From<Elapsed> for std::io::Error {
+    fn from(e: Elapsed) -> Self {
+        std::io::Error::new(std::io::ErrorKind::TimedOut, e)
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`timeout`](super::MultipartWriteExt::timeout).
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Timeout<Wr, Part> {
+        #[pin]
+        writer: Wr,
+        config: TimeoutConfig,
+        timer: Option<(Phase, Pin<Box<Sleep>>)>,
+        deadline: Option<Pin<Box<Sleep>>>,
+        _p: PhantomData<fn(Part)>,
+    }
+}
+
+impl<Wr, Part> Timeout<Wr, Part> {
+    pub(super) fn new(writer: Wr, config: TimeoutConfig) -> Self {
+        Self { writer, config, timer: None, deadline: None, _p: PhantomData }
+    }
+
+    /// Consumes `Timeout`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+
+    /// Poll the writer in the given phase, failing if the phase or the entire
+    /// write has taken too long.
+    fn poll_phase<T>(
+        self: Pin<&mut Self>,
+        phase: Phase,
+        cx: &mut Context<'_>,
+        f: impl FnOnce(Pin<&mut Wr>, &mut Context<'_>) -> Poll<Result<T, Wr::Error>>,
+    ) -> Poll<Result<T, Wr::Error>>
+    where
+        Wr: MultipartWrite<Part>,
+        Wr::Error: From<Elapsed>,
+    {
+        let this = self.project();
+
+        if let Some(limit) = this.config.deadline {
+            let deadline = this
+                .deadline
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep(limit)));
+            if deadline.as_mut().poll(cx).is_ready() {
+                // The expired deadline is kept so that everything else in this
+                // write fails too, until it is completed or aborted.
+                if phase == Phase::Complete {
+                    *this.deadline = None;
+                }
+                *this.timer = None;
+                let e = Elapsed::new(Phase::Deadline, limit);
+                return Poll::Ready(Err(e.into()));
+            }
+        }
+
+        if let Poll::Ready(res) = f(this.writer, cx) {
+            *this.timer = None;
+            // The writer is going to start a new write if it is used again.
+            if phase == Phase::Complete {
+                *this.deadline = None;
+            }
+            return Poll::Ready(res);
+        }
+
+        let Some(limit) = this.config.limit(phase) else {
+            return Poll::Pending;
+        };
+        let timer = match this.timer {
+            Some((p, timer)) if *p == phase => timer,
+            _ => {
+                let timer = Box::pin(tokio::time::sleep(limit));
+                &mut this.timer.insert((phase, timer)).1
+            },
+        };
+        if timer.as_mut().poll(cx).is_ready() {
+            *this.timer = None;
+            if phase == Phase::Complete {
+                *this.deadline = None;
+            }
+            return Poll::Ready(Err(Elapsed::new(phase, limit).into()));
+        }
+        Poll::Pending
+    }
+}
+
+impl<Wr, Part> FusedMultipartWrite<Part> for Timeout<Wr, Part>
+where
+    Wr: FusedMultipartWrite<Part>,
+    Wr::Error: From<Elapsed>,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr, Part> MultipartWrite<Part> for Timeout<Wr, Part>
+where
+    Wr: MultipartWrite<Part>,
+    Wr::Error: From<Elapsed>,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Wr::Recv;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.poll_phase(Phase::Ready, cx, |wr, cx| wr.poll_ready(cx))
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        if let Some(limit) = this.config.deadline
+            && this.deadline.as_ref().is_some_and(|d| d.is_elapsed())
+        {
+            return Err(Elapsed::new(Phase::Deadline, limit).into());
+        }
+        this.writer.start_send(part)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.poll_phase(Phase::Flush, cx, |wr, cx| wr.poll_flush(cx))
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        self.poll_phase(Phase::Complete, cx, |wr, cx| wr.poll_complete(cx))
+    }
+}
+
+impl<Wr, Part> AbortMultipartWrite<Part> for Timeout<Wr, Part>
+where
+    Wr: AbortMultipartWrite<Part>,
+    Wr::Error: From<Elapsed>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // Cleaning up is not subject to any of the time limits.
+        let this = self.project();
+        *this.timer = None;
+        *this.deadline = None;
+        this.writer.poll_abort(cx)
+    }
+}
+
+impl<Wr: Debug, Part> Debug for Timeout<Wr, Part> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timeout")
+            .field("writer", &self.writer)
+            .field("config", &self.config)
+            .field("timer", &self.timer)
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
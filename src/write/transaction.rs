@@ -0,0 +1,502 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use super::fanout_all::iter_pin_mut;
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// Returns a `MultipartWrite` that sends each part to every writer in the
+/// collection and completes them in two phases.
+///
+/// Completing the writer first flushes every branch, which is the prepare
+/// phase, and then completes every branch, which is the commit phase.  If a
+/// branch fails while a part is being written or in the prepare phase, every
+/// branch is aborted, including the one that failed, so none of them keeps
+/// any of the write.
+///
+/// A branch that has been completed cannot be rolled back, so if a branch
+/// fails in the commit phase only the branches that were not yet completed
+/// are aborted.  [`TransactionError::committed`] lists the branches that
+/// were, and their outputs are discarded.
+///
+/// After a failure the writer is poisoned: every method returns the same
+/// [`TransactionError`] until the writer is aborted, which aborts every
+/// branch and makes the writer usable again.  If a branch fails to abort,
+/// the rest are still aborted, the first error is returned, and the writer
+/// stays poisoned.  Because `start_send` cannot
+/// wait for the branches to be aborted, the error it returns does not
+/// include errors from aborting them, but the errors returned afterwards do.
+///
+/// The error type of the branches must be `Clone` for the error to be
+/// returned more than once.  Errors that are not can be wrapped in an `Arc`
+/// with [`map_err`](super::MultipartWriteExt::map_err).
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use multipart_write::{MultipartWriteExt as _, write};
+///
+/// let init: Vec<u8> = Vec::new();
+/// let writers = vec![write::extend(init.clone()), write::extend(init)];
+///
+/// let mut writer = write::transaction(writers);
+/// writer.send_flush(1).await.unwrap();
+/// writer.send_flush(2).await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!(out, vec![vec![1, 2], vec![1, 2]]);
+/// # })
+/// ```
+pub fn transaction<Wr, Part, I>(writers: I) -> Transaction<Wr, Part>
+where
+    I: IntoIterator<Item = Wr>,
+    Wr: AbortMultipartWrite<Part>,
+    Wr::Error: Clone,
+    Part: Clone,
+{
+    Transaction::new(writers.into_iter().collect())
+}
+
+/// The phase of a [`transaction`] in which a branch failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransactionPhase {
+    /// A part was being written to the branch.
+    Write,
+    /// The branch was being flushed before any branch was completed.
+    Prepare,
+    /// The branch was being completed.
+    Commit,
+    /// The branch was being aborted because the transaction was aborted.
+    Abort,
+}
+
+/// Error for [`transaction`] returned when one of the branches failed.
+#[derive(Debug, Clone)]
+pub struct TransactionError<E> {
+    branch: usize,
+    phase: TransactionPhase,
+    error: E,
+    committed: Vec<usize>,
+    abort_errors: Vec<(usize, E)>,
+}
+
+impl<E> TransactionError<E> {
+    fn new(branch: usize, phase: TransactionPhase, error: E) -> Self {
+        let committed = Vec::new();
+        Self { branch, phase, error, committed, abort_errors: Vec::new() }
+    }
+
+    /// Returns the index of the branch that failed.
+    pub fn branch(&self) -> usize {
+        self.branch
+    }
+
+    /// Returns the phase in which the branch failed.
+    pub fn phase(&self) -> TransactionPhase {
+        self.phase
+    }
+
+    /// Returns the error of the branch that failed.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Returns the indices of the branches that were already completed when
+    /// a branch failed in the commit phase.  These could not be rolled back.
+    pub fn committed(&self) -> &[usize] {
+        &self.committed
+    }
+
+    /// Returns the errors of the branches that failed to be aborted, with the
+    /// index of each branch.
+    pub fn abort_errors(&self) -> &[(usize, E)] {
+        &self.abort_errors
+    }
+
+    /// Consumes `TransactionError`, returning the error of the branch that
+    /// failed.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: Display> Display for TransactionError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let phase = match self.phase {
+            TransactionPhase::Write => "write",
+            TransactionPhase::Prepare => "prepare",
+            TransactionPhase::Commit => "commit",
+            TransactionPhase::Abort => "abort",
+        };
+        write!(f, "branch {} failed to {phase}: {}", self.branch, self.error)?;
+        if !self.abort_errors.is_empty() {
+            write!(
+                f,
+                " ({} branches failed to abort)",
+                self.abort_errors.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<E> std::error::Error for TransactionError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// `MultipartWrite` for [`transaction`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Transaction<Wr: MultipartWrite<Part>, Part> {
+    writers: Pin<Box<[Wr]>>,
+    outputs: Vec<Option<Wr::Output>>,
+    committing: bool,
+    aborting: Vec<bool>,
+    aborting_all: bool,
+    abort_error: Option<TransactionError<Wr::Error>>,
+    error: Option<TransactionError<Wr::Error>>,
+    _p: PhantomData<fn(Part)>,
+}
+
+impl<Wr: MultipartWrite<Part>, Part> Transaction<Wr, Part> {
+    fn new(writers: Box<[Wr]>) -> Self {
+        let outputs = writers.iter().map(|_| None).collect();
+        let aborting = vec![false; writers.len()];
+        Self {
+            writers: writers.into(),
+            outputs,
+            committing: false,
+            aborting,
+            aborting_all: false,
+            abort_error: None,
+            error: None,
+            _p: PhantomData,
+        }
+    }
+
+    /// Returns the number of writers in the transaction.
+    pub fn len(&self) -> usize {
+        self.writers.len()
+    }
+
+    /// Returns `true` if there are no writers in the transaction.
+    pub fn is_empty(&self) -> bool {
+        self.writers.is_empty()
+    }
+
+    /// Returns `true` if a branch failed and the writer has not been aborted
+    /// since.
+    pub fn is_poisoned(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Acquires a reference to the underlying writers.
+    pub fn get_ref(&self) -> &[Wr] {
+        &self.writers
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writers.
+    ///
+    /// It is inadvisable to directly write to the underlying writers.
+    pub fn get_pin_mut(&mut self) -> Pin<&mut [Wr]> {
+        self.writers.as_mut()
+    }
+
+    /// Poison the writer and start aborting every branch that has not been
+    /// completed.
+    fn fail(
+        &mut self,
+        branch: usize,
+        phase: TransactionPhase,
+        error: Wr::Error,
+    ) {
+        let mut e = TransactionError::new(branch, phase, error);
+        for (i, (out, aborting)) in
+            self.outputs.iter_mut().zip(self.aborting.iter_mut()).enumerate()
+        {
+            // A completed branch has nothing left of the write to abort.
+            *aborting = out.take().is_none();
+            if !*aborting {
+                e.committed.push(i);
+            }
+        }
+        self.committing = false;
+        self.error = Some(e);
+    }
+
+    /// Finish aborting the branches after a failure, and return the error
+    /// if the writer is poisoned.
+    fn poll_failure(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), TransactionError<Wr::Error>>>
+    where
+        Wr: AbortMultipartWrite<Part>,
+        Wr::Error: Clone,
+    {
+        let Some(error) = self.error.as_mut() else {
+            return Poll::Ready(Ok(()));
+        };
+        let mut is_ready = true;
+        let writers = iter_pin_mut(self.writers.as_mut());
+        for (i, (wr, aborting)) in
+            writers.zip(self.aborting.iter_mut()).enumerate()
+        {
+            if !*aborting {
+                continue;
+            }
+            match wr.poll_abort(cx) {
+                Poll::Ready(res) => {
+                    *aborting = false;
+                    if let Err(e) = res {
+                        error.abort_errors.push((i, e));
+                    }
+                },
+                Poll::Pending => is_ready = false,
+            }
+        }
+        if !is_ready {
+            return Poll::Pending;
+        }
+        Poll::Ready(Err(error.clone()))
+    }
+}
+
+// The writers are pinned on the heap and nothing else is pinned.
+impl<Wr: MultipartWrite<Part>, Part> Unpin for Transaction<Wr, Part> {}
+
+impl<Wr, Part> FusedMultipartWrite<Part> for Transaction<Wr, Part>
+where
+    Part: Clone,
+    Wr: AbortMultipartWrite<Part> + FusedMultipartWrite<Part>,
+    Wr::Error: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.writers.iter().any(|wr| wr.is_terminated())
+    }
+}
+
+impl<Wr, Part> MultipartWrite<Part> for Transaction<Wr, Part>
+where
+    Part: Clone,
+    Wr: AbortMultipartWrite<Part>,
+    Wr::Error: Clone,
+{
+    type Error = TransactionError<Wr::Error>;
+    type Output = Vec<Wr::Output>;
+    type Recv = Vec<Wr::Recv>;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_failure(cx))?;
+            let mut is_ready = true;
+            let mut failed = None;
+            let writers = iter_pin_mut(this.writers.as_mut());
+            for (i, wr) in writers.enumerate() {
+                match wr.poll_ready(cx) {
+                    Poll::Ready(Ok(())) => {},
+                    Poll::Ready(Err(e)) => {
+                        failed = Some((i, e));
+                        break;
+                    },
+                    Poll::Pending => is_ready = false,
+                }
+            }
+            if let Some((i, e)) = failed {
+                this.fail(i, TransactionPhase::Write, e);
+                continue;
+            }
+            return if is_ready { Poll::Ready(Ok(())) } else { Poll::Pending };
+        }
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: Part,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.get_mut();
+        if let Some(e) = &this.error {
+            return Err(e.clone());
+        }
+        let mut recv = Vec::with_capacity(this.writers.len());
+        let writers = iter_pin_mut(this.writers.as_mut());
+        let mut failed = None;
+        for (i, wr) in writers.enumerate() {
+            match wr.start_send(part.clone()) {
+                Ok(ret) => recv.push(ret),
+                Err(e) => {
+                    failed = Some((i, e));
+                    break;
+                },
+            }
+        }
+        match failed {
+            Some((i, e)) => {
+                this.fail(i, TransactionPhase::Write, e);
+                Err(this.error.clone().unwrap())
+            },
+            _ => Ok(recv),
+        }
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_failure(cx))?;
+            let mut is_ready = true;
+            let mut failed = None;
+            let writers = iter_pin_mut(this.writers.as_mut());
+            for (i, wr) in writers.enumerate() {
+                match wr.poll_flush(cx) {
+                    Poll::Ready(Ok(())) => {},
+                    Poll::Ready(Err(e)) => {
+                        failed = Some((i, e));
+                        break;
+                    },
+                    Poll::Pending => is_ready = false,
+                }
+            }
+            if let Some((i, e)) = failed {
+                this.fail(i, TransactionPhase::Prepare, e);
+                continue;
+            }
+            return if is_ready { Poll::Ready(Ok(())) } else { Poll::Pending };
+        }
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        if !self.committing {
+            ready!(self.as_mut().poll_flush(cx))?;
+            self.committing = true;
+        }
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_failure(cx))?;
+            let mut is_ready = true;
+            let mut failed = None;
+            let writers = iter_pin_mut(this.writers.as_mut());
+            for (i, (wr, out)) in
+                writers.zip(this.outputs.iter_mut()).enumerate()
+            {
+                if out.is_some() {
+                    continue;
+                }
+                match wr.poll_complete(cx) {
+                    Poll::Ready(Ok(v)) => *out = Some(v),
+                    Poll::Ready(Err(e)) => {
+                        failed = Some((i, e));
+                        break;
+                    },
+                    Poll::Pending => is_ready = false,
+                }
+            }
+            if let Some((i, e)) = failed {
+                this.fail(i, TransactionPhase::Commit, e);
+                continue;
+            }
+            if !is_ready {
+                return Poll::Pending;
+            }
+            this.committing = false;
+            let outputs =
+                this.outputs.iter_mut().map(|out| out.take().unwrap());
+            return Poll::Ready(Ok(outputs.collect()));
+        }
+    }
+}
+
+impl<Wr, Part> AbortMultipartWrite<Part> for Transaction<Wr, Part>
+where
+    Part: Clone,
+    Wr: AbortMultipartWrite<Part>,
+    Wr::Error: Clone,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if !this.aborting_all {
+            // Aborting every branch supersedes a failure in progress.
+            this.aborting_all = true;
+            this.committing = false;
+            this.outputs.iter_mut().for_each(|out| *out = None);
+            this.aborting.iter_mut().for_each(|a| *a = true);
+        }
+        let mut is_ready = true;
+        let writers = iter_pin_mut(this.writers.as_mut());
+        for (i, (wr, aborting)) in
+            writers.zip(this.aborting.iter_mut()).enumerate()
+        {
+            if !*aborting {
+                continue;
+            }
+            match wr.poll_abort(cx) {
+                Poll::Ready(res) => {
+                    *aborting = false;
+                    // The other branches are still aborted, and the first
+                    // error is returned once they have been.
+                    if let Err(e) = res {
+                        let phase = TransactionPhase::Abort;
+                        let error = TransactionError::new(i, phase, e);
+                        this.abort_error.get_or_insert(error);
+                    }
+                },
+                Poll::Pending => is_ready = false,
+            }
+        }
+        if !is_ready {
+            return Poll::Pending;
+        }
+        this.aborting_all = false;
+        match this.abort_error.take() {
+            // The poison is only cleared once every branch was aborted, so
+            // a writer that failed to abort stays poisoned.
+            Some(e) => {
+                this.error.get_or_insert_with(|| e.clone());
+                Poll::Ready(Err(e))
+            },
+            None => {
+                this.error = None;
+                Poll::Ready(Ok(()))
+            },
+        }
+    }
+}
+
+impl<Wr, Part> Debug for Transaction<Wr, Part>
+where
+    Wr: MultipartWrite<Part> + Debug,
+    Wr::Output: Debug,
+    Wr::Error: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transaction")
+            .field("writers", &self.writers)
+            .field("outputs", &self.outputs)
+            .field("committing", &self.committing)
+            .field("aborting", &self.aborting)
+            .field("aborting_all", &self.aborting_all)
+            .field("abort_error", &self.abort_error)
+            .field("error", &self.error)
+            .finish()
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`try_map_part`].
+    ///
+    /// [`try_map_part`]: super::MultipartWriteExt::try_map_part
+    #[must_use = "futures do nothing unless polled"]
+    pub struct TryMapPart<Wr, Part, P, E, F> {
+        #[pin]
+        writer: Wr,
+        f: F,
+        _p: PhantomData<fn(P) -> Result<Part, E>>,
+    }
+}
+
+impl<Wr, Part, P, E, F> TryMapPart<Wr, Part, P, E, F> {
+    pub(super) fn new(writer: Wr, f: F) -> Self {
+        Self { writer, f, _p: PhantomData }
+    }
+
+    /// Consumes `TryMapPart`, returning the underlying writer.
+    pub fn into_inner(self) -> Wr {
+        self.writer
+    }
+
+    /// Acquires a reference to the underlying writer.
+    pub fn get_ref(&self) -> &Wr {
+        &self.writer
+    }
+
+    /// Acquires a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut Wr {
+        &mut self.writer
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut Wr> {
+        self.project().writer
+    }
+}
+
+impl<Wr, Part, P, E, F> FusedMultipartWrite<P> for TryMapPart<Wr, Part, P, E, F>
+where
+    Wr: FusedMultipartWrite<Part>,
+    F: FnMut(P) -> Result<Part, E>,
+    E: Into<Wr::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        self.writer.is_terminated()
+    }
+}
+
+impl<Wr, Part, P, E, F> MultipartWrite<P> for TryMapPart<Wr, Part, P, E, F>
+where
+    Wr: MultipartWrite<Part>,
+    F: FnMut(P) -> Result<Part, E>,
+    E: Into<Wr::Error>,
+{
+    type Error = Wr::Error;
+    type Output = Wr::Output;
+    type Recv = Wr::Recv;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_ready(cx)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: P,
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        let part = (this.f)(part).map_err(Into::into)?;
+        this.writer.start_send(part)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_flush(cx)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        self.project().writer.poll_complete(cx)
+    }
+}
+
+impl<Wr, Part, P, E, F> AbortMultipartWrite<P> for TryMapPart<Wr, Part, P, E, F>
+where
+    Wr: AbortMultipartWrite<Part>,
+    F: FnMut(P) -> Result<Part, E>,
+    E: Into<Wr::Error>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.project().writer.poll_abort(cx)
+    }
+}
+
+impl<Wr: Debug, Part, P, E, F> Debug for TryMapPart<Wr, Part, P, E, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryMapPart").field("writer", &self.writer).finish()
+    }
+}
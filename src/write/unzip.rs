@@ -0,0 +1,197 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+
+use super::fanout::join;
+use crate::{AbortMultipartWrite, FusedMultipartWrite, MultipartWrite};
+
+/// Returns a `MultipartWrite` over pairs `(A, B)` that sends `A` to the first
+/// writer and `B` to the second writer.
+///
+/// # Examples
+///
+/// ```rust
+/// # futures::executor::block_on(async {
+/// use multipart_write::{MultipartWriteExt as _, write};
+///
+/// let names = write::extend(String::new());
+/// let ids: Vec<u8> = Vec::new();
+///
+/// let mut writer = write::unzip(names, ids);
+/// writer.send_flush(('a', 1)).await.unwrap();
+/// writer.send_flush(('b', 2)).await.unwrap();
+/// let out = writer.complete().await.unwrap();
+///
+/// assert_eq!(out, ("ab".to_string(), vec![1, 2]));
+/// # })
+/// ```
+pub fn unzip<WrA, WrB, A, B>(wra: WrA, wrb: WrB) -> Unzip<WrA, WrB, A, B>
+where
+    WrA: MultipartWrite<A>,
+    WrB: MultipartWrite<B, Error = WrA::Error>,
+{
+    Unzip::new(wra, wrb)
+}
+
+pin_project_lite::pin_project! {
+    /// `MultipartWrite` for [`unzip`].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Unzip<WrA: MultipartWrite<A>, WrB: MultipartWrite<B>, A, B> {
+        #[pin]
+        wra: WrA,
+        #[pin]
+        wrb: WrB,
+        woa: Option<WrA::Output>,
+        wob: Option<WrB::Output>,
+        _p: PhantomData<fn(A, B)>,
+    }
+}
+
+impl<WrA: MultipartWrite<A>, WrB: MultipartWrite<B>, A, B>
+    Unzip<WrA, WrB, A, B>
+{
+    fn new(wra: WrA, wrb: WrB) -> Self {
+        Self { wra, wrb, woa: None, wob: None, _p: PhantomData }
+    }
+
+    /// Consumes `Unzip`, returning the underlying writers.
+    pub fn into_inner(self) -> (WrA, WrB) {
+        (self.wra, self.wrb)
+    }
+
+    /// Acquires a reference to the underlying writers.
+    pub fn get_ref(&self) -> (&WrA, &WrB) {
+        (&self.wra, &self.wrb)
+    }
+
+    /// Acquires a mutable reference to the underlying writers.
+    ///
+    /// It is inadvisable to directly write to the underlying writers.
+    pub fn get_mut(&mut self) -> (&mut WrA, &mut WrB) {
+        (&mut self.wra, &mut self.wrb)
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writers.
+    ///
+    /// It is inadvisable to directly write to the underlying writers.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> (Pin<&mut WrA>, Pin<&mut WrB>) {
+        let this = self.project();
+        (this.wra, this.wrb)
+    }
+}
+
+impl<WrA, WrB, A, B> FusedMultipartWrite<(A, B)> for Unzip<WrA, WrB, A, B>
+where
+    WrA: FusedMultipartWrite<A>,
+    WrB: FusedMultipartWrite<B, Error = WrA::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        self.wra.is_terminated() || self.wrb.is_terminated()
+    }
+}
+
+impl<WrA, WrB, A, B> MultipartWrite<(A, B)> for Unzip<WrA, WrB, A, B>
+where
+    WrA: MultipartWrite<A>,
+    WrB: MultipartWrite<B, Error = WrA::Error>,
+{
+    type Error = WrA::Error;
+    type Output = (WrA::Output, WrB::Output);
+    type Recv = (WrA::Recv, WrB::Recv);
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        let res1 = this.wra.poll_ready(cx);
+        let res2 = this.wrb.poll_ready(cx);
+        join(res1, res2)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        part: (A, B),
+    ) -> Result<Self::Recv, Self::Error> {
+        let this = self.project();
+        let (a, b) = part;
+        let ret1 = this.wra.start_send(a)?;
+        let ret2 = this.wrb.start_send(b)?;
+        Ok((ret1, ret2))
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        let res1 = this.wra.poll_flush(cx);
+        let res2 = this.wrb.poll_flush(cx);
+        join(res1, res2)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        let this = self.project();
+        let res1 = match this.woa {
+            Some(_) => Poll::Ready(Ok(())),
+            None => this.wra.poll_complete(cx).map_ok(|out| {
+                *this.woa = Some(out);
+            }),
+        };
+        let res2 = match this.wob {
+            Some(_) => Poll::Ready(Ok(())),
+            None => this.wrb.poll_complete(cx).map_ok(|out| {
+                *this.wob = Some(out);
+            }),
+        };
+        if let Err(e) = ready!(join(res1, res2)) {
+            *this.woa = None;
+            *this.wob = None;
+            return Poll::Ready(Err(e));
+        }
+        let out1 = this.woa.take().unwrap();
+        let out2 = this.wob.take().unwrap();
+        Poll::Ready(Ok((out1, out2)))
+    }
+}
+
+impl<WrA, WrB, A, B> AbortMultipartWrite<(A, B)> for Unzip<WrA, WrB, A, B>
+where
+    WrA: AbortMultipartWrite<A>,
+    WrB: AbortMultipartWrite<B, Error = WrA::Error>,
+{
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        let this = self.project();
+        *this.woa = None;
+        *this.wob = None;
+        let res1 = this.wra.poll_abort(cx);
+        let res2 = this.wrb.poll_abort(cx);
+        join(res1, res2)
+    }
+}
+
+impl<WrA, WrB, A, B> Debug for Unzip<WrA, WrB, A, B>
+where
+    WrA: MultipartWrite<A> + Debug,
+    WrB: MultipartWrite<B> + Debug,
+    WrA::Output: Debug,
+    WrB::Output: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Unzip")
+            .field("wra", &self.wra)
+            .field("wrb", &self.wrb)
+            .field("woa", &self.woa)
+            .field("wob", &self.wob)
+            .finish()
+    }
+}
@@ -5,7 +5,8 @@ use futures::future;
 use futures::stream::{StreamExt as _, iter};
 use multipart_write::stream::MultipartStreamExt as _;
 use multipart_write::{
-    FusedMultipartWrite, MultipartWrite, MultipartWriteExt as _,
+    AbortMultipartWrite, FusedMultipartWrite, MultipartWrite,
+    MultipartWriteExt as _,
 };
 
 #[derive(Clone, Debug)]
@@ -13,6 +14,7 @@ struct TestWriter {
     inner: Vec<usize>,
     multiplier: usize,
     completed: usize,
+    aborted: usize,
     max_completed: Option<usize>,
 }
 
@@ -22,6 +24,7 @@ impl Default for TestWriter {
             inner: Vec::new(),
             multiplier: 1,
             completed: 0,
+            aborted: 0,
             max_completed: None,
         }
     }
@@ -33,6 +36,7 @@ impl TestWriter {
             multiplier,
             inner: Vec::new(),
             completed: 0,
+            aborted: 0,
             max_completed: None,
         }
     }
@@ -80,6 +84,17 @@ impl MultipartWrite<usize> for TestWriter {
     }
 }
 
+impl AbortMultipartWrite<usize> for TestWriter {
+    fn poll_abort(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.aborted += 1;
+        self.inner.clear();
+        Poll::Ready(Ok(()))
+    }
+}
+
 impl FusedMultipartWrite<usize> for TestWriter {
     fn is_terminated(&self) -> bool {
         self.max_completed.is_some_and(|n| n <= self.completed)
@@ -126,6 +141,231 @@ impl MultipartWrite<Vec<usize>> for OtherTestWriter {
     }
 }
 
+/// Returns `Poll::Pending` from `poll_complete` a number of times before
+/// completing the inner writer.
+#[derive(Debug, Clone, Default)]
+struct SlowWriter {
+    inner: TestWriter,
+    delay: usize,
+    remaining: usize,
+}
+
+impl SlowWriter {
+    fn new(inner: TestWriter, delay: usize) -> Self {
+        Self { inner, delay, remaining: delay }
+    }
+}
+
+impl MultipartWrite<usize> for SlowWriter {
+    type Error = String;
+    type Output = Vec<usize>;
+    type Recv = usize;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), String>> {
+        self.inner.poll_ready_unpin(cx)
+    }
+
+    fn start_send(
+        mut self: Pin<&mut Self>,
+        part: usize,
+    ) -> Result<usize, String> {
+        Pin::new(&mut self.inner).start_send(part)
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_flush_unpin(cx)
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.remaining = self.delay;
+        self.inner.poll_complete_unpin(cx)
+    }
+}
+
+/// Returns `Poll::Pending` from `poll_abort` a number of times before
+/// aborting the inner writer, and can fail to abort it.
+#[derive(Debug, Clone, Default)]
+struct AbortWriter {
+    inner: TestWriter,
+    remaining: usize,
+    fail: bool,
+}
+
+impl AbortWriter {
+    fn new(delay: usize, fail: bool) -> Self {
+        Self { inner: TestWriter::default(), remaining: delay, fail }
+    }
+}
+
+impl MultipartWrite<usize> for AbortWriter {
+    type Error = String;
+    type Output = Vec<usize>;
+    type Recv = usize;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), String>> {
+        self.inner.poll_ready_unpin(cx)
+    }
+
+    fn start_send(
+        mut self: Pin<&mut Self>,
+        part: usize,
+    ) -> Result<usize, String> {
+        Pin::new(&mut self.inner).start_send(part)
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_flush_unpin(cx)
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        self.inner.poll_complete_unpin(cx)
+    }
+}
+
+impl AbortMultipartWrite<usize> for AbortWriter {
+    fn poll_abort(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        if self.fail {
+            return Poll::Ready(Err("abort failed".to_string()));
+        }
+        Pin::new(&mut self.inner).poll_abort(cx)
+    }
+}
+
+/// Fails a number of calls to `start_send` and `poll_complete` before
+/// forwarding them to the inner writer.
+#[derive(Debug, Clone, Default)]
+struct FlakyWriter {
+    inner: TestWriter,
+    send_failures: usize,
+    complete_failures: usize,
+}
+
+impl FlakyWriter {
+    fn new(send_failures: usize, complete_failures: usize) -> Self {
+        Self { inner: TestWriter::default(), send_failures, complete_failures }
+    }
+}
+
+impl MultipartWrite<usize> for FlakyWriter {
+    type Error = String;
+    type Output = Vec<usize>;
+    type Recv = usize;
+
+    fn poll_ready(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), String>> {
+        self.inner.poll_ready_unpin(cx)
+    }
+
+    fn start_send(
+        mut self: Pin<&mut Self>,
+        part: usize,
+    ) -> Result<usize, String> {
+        if self.send_failures > 0 {
+            self.send_failures -= 1;
+            return Err(format!("failed to send {part}"));
+        }
+        Pin::new(&mut self.inner).start_send(part)
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_flush_unpin(cx)
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        if self.complete_failures > 0 {
+            self.complete_failures -= 1;
+            return Poll::Ready(Err("failed to complete".into()));
+        }
+        self.inner.poll_complete_unpin(cx)
+    }
+}
+
+/// Shares the inner writer so that it can be checked after this is dropped.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Default)]
+struct SharedWriter(std::sync::Arc<std::sync::Mutex<TestWriter>>);
+
+#[cfg(feature = "tokio")]
+impl MultipartWrite<usize> for SharedWriter {
+    type Error = String;
+    type Output = Vec<usize>;
+    type Recv = usize;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), String>> {
+        self.0.lock().unwrap().poll_ready_unpin(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, part: usize) -> Result<usize, String> {
+        Pin::new(&mut *self.0.lock().unwrap()).start_send(part)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.0.lock().unwrap().poll_flush_unpin(cx)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Output, Self::Error>> {
+        self.0.lock().unwrap().poll_complete_unpin(cx)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AbortMultipartWrite<usize> for SharedWriter {
+    fn poll_abort(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *self.0.lock().unwrap()).poll_abort(cx)
+    }
+}
+
 #[tokio::test]
 async fn trait_futures() {
     let mut writer = TestWriter::default();
@@ -167,6 +407,125 @@ async fn ready_part_writer() {
     assert_eq!(out, vec![6, 2, 4]);
 }
 
+#[tokio::test]
+async fn chunks_writer() {
+    use multipart_write::write;
+
+    let mut writer = write::chunks(2, OtherTestWriter::default());
+    let r1 = writer.feed(1).await.unwrap();
+    let r2 = writer.feed(2).await.unwrap();
+    let r3 = writer.feed(3).await.unwrap();
+    assert_eq!(writer.pending(), &[3]);
+    writer.flush().await.unwrap();
+    let flushed = Pin::new(&mut writer).take_recv();
+    let r4 = writer.send_flush(4).await.unwrap();
+    let r5 = writer.feed(5).await.unwrap();
+    let out = writer.complete().await.unwrap();
+
+    assert_eq!((r1, r2, r3, flushed), (None, Some(1), None, Some(2)));
+    assert_eq!((r4, r5), (None, None));
+    assert_eq!(out, "3,3,4,5");
+}
+
+#[tokio::test]
+async fn map_part_writers() {
+    let mut writer = TestWriter::new(2).map_part(|x: &str| x.len());
+    let r1 = writer.send_flush("abc").await.unwrap();
+    let r2 = writer.send_flush("d").await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!((r1, r2), (3, 1));
+    assert_eq!(out, vec![6, 2]);
+
+    let mut writer = TestWriter::new(2)
+        .try_map_part(|x: &str| x.parse::<usize>().map_err(|e| e.to_string()));
+    writer.send_flush("3").await.unwrap();
+    assert!(writer.send_flush("a").await.is_err());
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![6]);
+
+    let mut writer =
+        TestWriter::new(2).and_then_part(|x: &str| future::ready(Ok(x.len())));
+    let r1 = writer.send_flush("abc").await.unwrap();
+    let r2 = writer.send_flush("d").await.unwrap();
+    let r3 = writer.send_flush("ef").await.unwrap();
+    let out = writer.complete().await.unwrap();
+    let last = Pin::new(&mut writer).take_recv();
+    assert_eq!((r1, r2, r3, last), (None, Some(3), Some(1), Some(2)));
+    assert_eq!(out, vec![6, 2, 4]);
+    // The value for the last part is kept for the next write if not taken.
+    writer.send_flush("g").await.unwrap();
+    writer.complete().await.unwrap();
+    let r1 = writer.send_flush("hi").await.unwrap();
+    assert_eq!(r1, Some(1));
+
+    // The writer can still be used after the future fails.
+    let mut writer = TestWriter::new(2).and_then_part(|x: &str| {
+        future::ready(x.parse::<usize>().map_err(|e| e.to_string()))
+    });
+    writer.send_flush("3").await.unwrap();
+    assert!(writer.send_flush("a").await.is_err());
+    writer.send_flush("4").await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![6, 8]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn ready_part_concurrent_writer() {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    // Later parts are ready sooner.
+    let delayed = |n: usize| {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_secs(4 - n as u64)).await;
+            Ok(n)
+        })
+    };
+
+    let mut writer = TestWriter::default().ready_part_concurrent(3, delayed);
+    let start = Instant::now();
+    for n in 1..=3 {
+        writer.feed(n).await.unwrap();
+    }
+    assert_eq!(writer.in_flight(), 3);
+    let out = writer.complete().await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+    assert_eq!(out, vec![1, 2, 3]);
+
+    let mut writer = TestWriter::default().ready_part_unordered(3, delayed);
+    let start = Instant::now();
+    for n in 1..=3 {
+        writer.feed(n).await.unwrap();
+    }
+    let out = writer.complete().await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+    assert_eq!(out, vec![3, 2, 1]);
+
+    // Only two futures can be in flight, so the third part has to wait for
+    // the first to finish.
+    let mut writer = TestWriter::default().ready_part_concurrent(2, delayed);
+    let start = Instant::now();
+    for n in 1..=3 {
+        writer.feed(n).await.unwrap();
+    }
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+    let out = writer.complete().await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(4));
+    assert_eq!(out, vec![1, 2, 3]);
+
+    // The writer can still be used after one of the futures fails.
+    let fail_on_2 = |n: usize| {
+        future::ready(if n == 2 { Err("bad part".to_string()) } else { Ok(n) })
+    };
+    let mut writer = TestWriter::default().ready_part_concurrent(2, fail_on_2);
+    writer.send_flush(1).await.unwrap();
+    assert!(writer.send_flush(2).await.is_err());
+    writer.send_flush(3).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![1, 3]);
+}
+
 #[tokio::test]
 async fn lift_writer() {
     let writer = OtherTestWriter::default().lift(TestWriter::default());
@@ -193,6 +552,40 @@ async fn try_complete_when_stream() {
     assert_eq!(out3, vec![11, 12]);
 }
 
+#[tokio::test]
+async fn complete_each_stream() {
+    let mut made = 0;
+    let make = || {
+        made += 1;
+        future::ready(Ok::<_, String>(TestWriter::default()))
+    };
+    let outputs = iter(1..=12)
+        .complete_each(make, |ret| ret % 5 == 0)
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(
+        outputs,
+        vec![
+            Ok(vec![1, 2, 3, 4, 5]),
+            Ok(vec![6, 7, 8, 9, 10]),
+            Ok(vec![11, 12])
+        ]
+    );
+
+    // No writer is made for an output that would be empty.
+    made = 0;
+    let make = || {
+        made += 1;
+        future::ready(Ok::<_, String>(TestWriter::default()))
+    };
+    let outputs = iter(1..=10)
+        .complete_each(make, |ret| ret % 5 == 0)
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(made, 2);
+}
+
 #[tokio::test]
 async fn complete_with_future() {
     let writer =
@@ -230,3 +623,1022 @@ async fn stream_ends_on_fused_writer() {
     assert_eq!(outputs.pop(), Some(vec![1, 2]));
     assert!(outputs.pop().is_none());
 }
+
+#[cfg(feature = "futures-sink")]
+#[tokio::test]
+async fn from_sink_writer() {
+    use multipart_write::write;
+
+    let sink: Vec<usize> = Vec::new();
+    let mut writer =
+        write::from_sink_with(sink, |vs| std::mem::take(vs.get_mut()));
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(2).await.unwrap();
+    let out = writer.complete().await.unwrap();
+
+    assert_eq!(out, vec![1, 2]);
+    assert!(writer.is_terminated());
+}
+
+#[cfg(feature = "futures-sink")]
+#[tokio::test]
+async fn into_sink_from_writer() {
+    use futures::sink::SinkExt as _;
+
+    let mut sink = TestWriter::new(2).into_sink();
+    sink.send_all(&mut iter(1..=3).map(Ok)).await.unwrap();
+    sink.close().await.unwrap();
+    // Closing again does not complete the writer a second time.
+    sink.close().await.unwrap();
+
+    assert_eq!(sink.get_ref().completed, 1);
+    assert_eq!(Pin::new(&mut sink).take_output(), Some(vec![2, 4, 6]));
+    // Not even after the output was taken.
+    sink.close().await.unwrap();
+    assert_eq!(sink.get_ref().completed, 1);
+    assert_eq!(sink.into_output(), None);
+}
+
+#[tokio::test]
+async fn fanout_all_writers() {
+    use multipart_write::write;
+
+    let writers = (1..=3).map(TestWriter::new);
+    let mut writer = write::fanout_all(writers);
+    let ret = writer.send_flush(1).await.unwrap();
+    writer.send_flush(2).await.unwrap();
+    let out = writer.complete().await.unwrap();
+
+    assert_eq!(ret, vec![1, 1, 1]);
+    assert_eq!(out, vec![vec![1, 2], vec![2, 4], vec![3, 6]]);
+}
+
+#[tokio::test]
+async fn fanout_tuple_writers() {
+    use multipart_write::write;
+
+    let writers = (
+        TestWriter::new(1),
+        TestWriter::new(2).map_ok(|vs| vs.iter().sum::<usize>()),
+        OtherTestWriter::default().lift(TestWriter::default()),
+    );
+    let mut writer = write::fanout_tuple(writers);
+    for n in 1..=3 {
+        writer.feed(n).await.unwrap();
+    }
+    let out = writer.complete().await.unwrap();
+
+    assert_eq!(out, (vec![1, 2, 3], 12, "6".to_string()));
+
+    // The writer is terminated as soon as any of the writers is.
+    let writers = (TestWriter::new(1), TestWriter::new(2).max_completed(1));
+    let mut writer = write::fanout_tuple(writers);
+    writer.send_flush(1).await.unwrap();
+    assert!(!writer.is_terminated());
+    writer.complete().await.unwrap();
+    assert!(writer.is_terminated());
+}
+
+#[tokio::test]
+async fn fanout_keeps_completed_outputs() {
+    use multipart_write::write;
+
+    let writers = [FlakyWriter::new(0, 0), FlakyWriter::new(0, 1)];
+    let mut writer = write::fanout_all(writers);
+    writer.send_flush(1).await.unwrap();
+    assert!(writer.complete().await.is_err());
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![vec![1], vec![1]]);
+    assert_eq!(writer.get_ref()[0].inner.completed, 1);
+
+    let writers = (FlakyWriter::new(0, 1), FlakyWriter::new(0, 0));
+    let mut writer = write::fanout_tuple(writers);
+    writer.send_flush(1).await.unwrap();
+    assert!(writer.complete().await.is_err());
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, (vec![1], vec![1]));
+    assert_eq!(writer.get_ref().1.inner.completed, 1);
+
+    let mut writer = TestWriter::default().fanout(FlakyWriter::new(0, 1));
+    writer.send_flush(1).await.unwrap();
+    assert!(writer.complete().await.is_err());
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, (vec![1], vec![1]));
+}
+
+#[tokio::test]
+async fn fanout_completes_concurrently() {
+    let wr1 = SlowWriter::new(TestWriter::new(1), 2);
+    let wr2 = SlowWriter::new(TestWriter::new(2), 3);
+    let mut writer = wr1.fanout(wr2);
+    writer.feed(1).await.unwrap();
+    writer.feed(2).await.unwrap();
+
+    let mut polls = 0;
+    let out = future::poll_fn(|cx| {
+        polls += 1;
+        writer.poll_complete_unpin(cx)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(polls, 4);
+    assert_eq!(out, (vec![1, 2], vec![2, 4]));
+}
+
+#[tokio::test]
+async fn route_writer() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use futures::FutureExt as _;
+    use multipart_write::Either;
+
+    struct Gated {
+        inner: TestWriter,
+        open: Arc<AtomicBool>,
+    }
+
+    impl MultipartWrite<usize> for Gated {
+        type Error = String;
+        type Output = Vec<usize>;
+        type Recv = usize;
+
+        fn poll_ready(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<(), String>> {
+            if self.open.load(Ordering::SeqCst) {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        }
+
+        fn start_send(
+            mut self: Pin<&mut Self>,
+            part: usize,
+        ) -> Result<usize, String> {
+            Pin::new(&mut self.inner).start_send(part)
+        }
+
+        fn poll_flush(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<(), String>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_complete(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<Vec<usize>, String>> {
+            Pin::new(&mut self.inner).poll_complete(cx)
+        }
+    }
+
+    let mut writer = TestWriter::new(1)
+        .route(OtherTestWriter::default().lift(TestWriter::new(10)), |n| {
+            n % 3 != 0
+        });
+    let mut rets = Vec::new();
+    for n in 1..=6 {
+        rets.push(writer.feed(n).await.unwrap());
+    }
+    let out = writer.complete().await.unwrap();
+
+    assert!(matches!(rets[2], Either::Right(_)));
+    assert_eq!(rets.iter().filter(|r| r.is_left()).count(), 4);
+    assert_eq!(out, (vec![1, 2, 4, 5], "90".to_string()));
+
+    // Being ready waits on both writers, but a part can be sent as soon as
+    // the writer it is routed to is ready.
+    let open = Arc::new(AtomicBool::new(false));
+    let gated = Gated { inner: TestWriter::default(), open: open.clone() };
+    let mut writer = TestWriter::default().route(gated, |n| n % 2 == 1);
+    assert!(writer.feed(1).now_or_never().is_none());
+    for n in [1, 3] {
+        future::poll_fn(|cx| Pin::new(&mut writer).poll_ready_part(&n, cx))
+            .await
+            .unwrap();
+        let ret = Pin::new(&mut writer).start_send(n).unwrap();
+        assert_eq!(ret, Either::Left(n));
+    }
+    open.store(true, Ordering::SeqCst);
+    let ret = writer.feed(2).await.unwrap();
+    assert_eq!(ret, Either::Right(2));
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, (vec![1, 3], vec![2]));
+}
+
+#[tokio::test]
+async fn unzip_writer() {
+    use multipart_write::write;
+
+    let writer = write::unzip(
+        TestWriter::new(2),
+        OtherTestWriter::default().lift(SlowWriter::new(TestWriter::new(3), 1)),
+    );
+    let out = iter(1..=3).map(|n| (n, n)).complete_with(writer).await.unwrap();
+
+    assert_eq!(out, (vec![2, 4, 6], "18".to_string()));
+}
+
+#[tokio::test]
+async fn either_writer() {
+    use multipart_write::Either;
+
+    let writers = [
+        Either::Left(TestWriter::new(2)),
+        Either::Right(SlowWriter::new(TestWriter::new(2), 2)),
+    ];
+    for writer in writers {
+        let out = iter(1..=3).complete_with(writer).await.unwrap();
+        assert_eq!(out, vec![2, 4, 6]);
+    }
+}
+
+#[tokio::test]
+async fn option_writer() {
+    let mut writer = Some(TestWriter::new(2));
+    let ret = writer.send_flush(1).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!((ret, out), (Some(1), Some(vec![2])));
+
+    let mut writer: Option<TestWriter> = None;
+    let ret = writer.send_flush(1).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!((ret, out), (None, None));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn retry_writer() {
+    use std::time::Duration;
+
+    use multipart_write::write::ExponentialBackoff;
+    use tokio::time::Instant;
+
+    let policy = ExponentialBackoff::new(Duration::from_secs(1));
+    let mut writer = FlakyWriter::new(2, 1).retry(policy.retry_complete());
+    let start = Instant::now();
+
+    // The first send fails and the part is sent again when flushing: 1s after
+    // the first failure and 2s after the second.
+    let r1 = writer.send_flush(1).await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+    assert_eq!(Pin::new(&mut writer).take_recv(), Some(1));
+    let r2 = writer.send_flush(2).await.unwrap();
+    let out = writer.complete().await.unwrap();
+
+    assert_eq!((r1, r2), (None, Some(2)));
+    assert_eq!(out, vec![1, 2]);
+    assert_eq!(start.elapsed(), Duration::from_secs(4));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn retry_writer_gives_up() {
+    use std::time::Duration;
+
+    use multipart_write::write::ExponentialBackoff;
+
+    let policy = ExponentialBackoff::new(Duration::from_secs(1))
+        .max_retries(3)
+        .retry_if(|e: &String| e.starts_with("failed to send"));
+    let mut writer = FlakyWriter::new(4, 1).retry(policy);
+    let res = writer.send_flush(1).await;
+    assert_eq!(res, Err("failed to send 1".to_string()));
+
+    let mut writer = FlakyWriter::new(0, 1).retry(policy);
+    writer.send_flush(1).await.unwrap();
+    let res = writer.complete().await;
+    assert_eq!(res, Err("failed to complete".to_string()));
+
+    // Errors from completing are only retried if the policy opts in.
+    let policy = ExponentialBackoff::new(Duration::from_secs(1));
+    let mut writer = FlakyWriter::new(0, 1).retry(policy);
+    writer.send_flush(1).await.unwrap();
+    let res = writer.complete().await;
+    assert_eq!(res, Err("failed to complete".to_string()));
+    assert_eq!(writer.complete().await.unwrap(), vec![1]);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn timeout_writer() {
+    use std::io;
+    use std::time::Duration;
+
+    use multipart_write::write::{Elapsed, Phase, TimeoutConfig};
+
+    let slow_complete = |res| {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            res
+        })
+    };
+    let elapsed = |e: io::Error| {
+        assert_eq!(e.kind(), io::ErrorKind::TimedOut);
+        *e.into_inner().unwrap().downcast::<Elapsed>().unwrap()
+    };
+
+    let config = TimeoutConfig::new().complete(Duration::from_secs(20));
+    let mut writer = TestWriter::default()
+        .map_err(io::Error::other)
+        .then(slow_complete)
+        .timeout(config);
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(2).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![1, 2]);
+
+    let config = TimeoutConfig::new().complete(Duration::from_secs(5));
+    let mut writer = TestWriter::default()
+        .map_err(io::Error::other)
+        .then(slow_complete)
+        .timeout(config);
+    writer.send_flush(1).await.unwrap();
+    let e = elapsed(writer.complete().await.unwrap_err());
+    assert_eq!(e.phase(), Phase::Complete);
+    assert_eq!(e.limit(), Duration::from_secs(5));
+
+    let config = TimeoutConfig::new().deadline(Duration::from_secs(15));
+    let mut writer = TestWriter::default()
+        .map_err(io::Error::other)
+        .then(slow_complete)
+        .timeout(config);
+    writer.send_flush(1).await.unwrap();
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    let e = elapsed(writer.complete().await.unwrap_err());
+    assert_eq!(e.phase(), Phase::Deadline);
+
+    // Everything fails after the deadline until the write is ended.
+    let config = TimeoutConfig::new().deadline(Duration::from_secs(5));
+    let mut writer =
+        TestWriter::default().map_err(io::Error::other).timeout(config);
+    writer.send_flush(1).await.unwrap();
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    let e = elapsed(writer.flush().await.unwrap_err());
+    assert_eq!(e.phase(), Phase::Deadline);
+    let e = elapsed(writer.send_flush(2).await.unwrap_err());
+    assert_eq!(e.phase(), Phase::Deadline);
+    let e = elapsed(Pin::new(&mut writer).start_send(2).unwrap_err());
+    assert_eq!(e.phase(), Phase::Deadline);
+    let e = elapsed(writer.complete().await.unwrap_err());
+    assert_eq!(e.phase(), Phase::Deadline);
+    writer.send_flush(3).await.unwrap();
+    assert_eq!(writer.complete().await.unwrap(), vec![1, 3]);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn rate_limit_writer() {
+    use std::time::Duration;
+
+    use multipart_write::write::Quota;
+    use tokio::time::Instant;
+
+    // Two parts can be sent at once and then one every half second.
+    let mut writer = TestWriter::default().rate_limit(Quota::per_second(2));
+    let start = Instant::now();
+    for n in 1..=6 {
+        writer.send_flush(n).await.unwrap();
+    }
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![1, 2, 3, 4, 5, 6]);
+
+    // A heavy part puts the writer into deficit for the next part.
+    let quota = Quota::per_second(10).burst(5);
+    let mut writer = TestWriter::default().rate_limit_by(quota, |n| *n as u64);
+    let start = Instant::now();
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(13).await.unwrap();
+    assert_eq!(start.elapsed(), Duration::ZERO);
+    writer.send_flush(1).await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn parallel_writer() {
+    use std::time::Duration;
+
+    use multipart_write::write;
+    use tokio::time::Instant;
+
+    // Later parts finish sooner.
+    let mut writer = write::parallel(2, |_, part: u64| async move {
+        tokio::time::sleep(Duration::from_secs(4 - part)).await;
+        Ok::<_, String>(part * 10)
+    });
+    let start = Instant::now();
+    let n1 = writer.feed(1).await.unwrap();
+    let n2 = writer.feed(2).await.unwrap();
+    assert_eq!(writer.in_flight(), 2);
+    // The third part has to wait for the second to finish.
+    let n3 = writer.feed(3).await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+    let out = writer.complete().await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+    assert_eq!((n1, n2, n3), (1, 2, 3));
+    assert_eq!(out, vec![(1, 10), (2, 20), (3, 30)]);
+
+    // Part numbers start again after the writer is completed.
+    let n1 = writer.send_flush(3).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(n1, 1);
+    assert_eq!(out, vec![(1, 30)]);
+
+    // A part that failed leaves a gap in the part numbers.
+    let mut writer = write::parallel(2, |_, part: u64| async move {
+        if part == 2 { Err("bad part".to_string()) } else { Ok(part) }
+    });
+    writer.feed(1).await.unwrap();
+    writer.feed(2).await.unwrap();
+    assert_eq!(writer.flush().await, Err("bad part".to_string()));
+    writer.feed(3).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![(1, 1), (3, 3)]);
+    assert!(!writer.is_terminated());
+}
+
+#[cfg(feature = "bytes")]
+#[tokio::test]
+async fn rechunk_writer() {
+    use bytes::Bytes;
+    use multipart_write::io;
+
+    let parts: Vec<Bytes> = Vec::new();
+    let mut writer = io::rechunk(3, 5, parts);
+    writer.send_flush(Bytes::from_static(b"a")).await.unwrap();
+    writer.send_flush(Bytes::from_static(b"b")).await.unwrap();
+    assert_eq!(writer.buffered_len(), 2);
+    writer.send_flush(Bytes::from_static(b"cdefghijkl")).await.unwrap();
+    // The last two bytes are too few to be sent before completing.
+    assert_eq!(writer.buffered_len(), 2);
+    writer.send_flush(Bytes::from_static(b"n")).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec!["abcde", "fghij", "kln"]);
+
+    // A part that is only split is not copied.
+    let part = Bytes::from_static(b"abcdefgh");
+    let parts: Vec<Bytes> = Vec::new();
+    let mut writer = io::rechunk(3, 5, parts);
+    writer.send_flush(part.clone()).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec!["abcde", "fgh"]);
+    assert_eq!(out[0].as_ptr(), part.as_ptr());
+    assert_eq!(out[1].as_ptr(), part[5..].as_ptr());
+
+    // The part size doubles after parts 4, 6 and 7 of 8.
+    let parts: Vec<Bytes> = Vec::new();
+    let mut writer = io::rechunk(1, 4, parts).adaptive(8);
+    for _ in 0..12 {
+        writer.feed(Bytes::from_static(b"x")).await.unwrap();
+    }
+    assert_eq!(writer.part_size(), 4);
+    let out = writer.complete().await.unwrap();
+    let sizes: Vec<usize> = out.iter().map(Bytes::len).collect();
+    assert_eq!(sizes, vec![1, 1, 1, 1, 2, 2, 4]);
+    assert_eq!(writer.parts_sent(), 0);
+}
+
+#[tokio::test]
+async fn rolling_writer() {
+    use std::time::Duration;
+
+    use multipart_write::write::{self, RollPolicy};
+
+    let policy = RollPolicy::new().max_parts(3).max_bytes(10);
+    let mut writer =
+        write::rolling_by(TestWriter::default, policy, |n| *n as u64);
+    let mut recv = Vec::new();
+    for n in [1, 2, 3, 4, 9, 5] {
+        recv.push(writer.send_flush(n).await.unwrap());
+    }
+    let out = writer.complete().await.unwrap();
+    assert_eq!(recv, vec![(0, 1), (0, 2), (0, 3), (1, 4), (1, 9), (2, 5)]);
+    assert_eq!(out, vec![vec![1, 2, 3], vec![4, 9], vec![5]]);
+
+    // Generations start again after completing.
+    assert_eq!(writer.generation(), 0);
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![vec![]]);
+
+    let policy = RollPolicy::new().max_age(Duration::ZERO);
+    let mut writer = write::rolling(TestWriter::default, policy);
+    writer.send_flush(1).await.unwrap();
+    let (generation, _) = writer.send_flush(2).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(generation, 1);
+    assert_eq!(out, vec![vec![1], vec![2]]);
+
+    // The next writer is only made when there is a part for it.
+    let made = std::cell::Cell::new(0);
+    let make = || {
+        made.set(made.get() + 1);
+        TestWriter::default()
+    };
+    let mut writer = write::rolling(make, RollPolicy::new().max_parts(1));
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(2).await.unwrap();
+    writer.complete().await.unwrap();
+    assert_eq!(made.get(), 2);
+
+    // Aborting keeps the outputs of the generations that were completed.
+    writer.send_flush(3).await.unwrap();
+    writer.send_flush(4).await.unwrap();
+    writer.abort().await.unwrap();
+    assert_eq!(made.get(), 4);
+    let committed = Pin::new(&mut writer).take_committed();
+    assert_eq!(committed, vec![vec![3]]);
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![vec![]]);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn complete_every_stream() {
+    use std::time::Duration;
+
+    use futures::stream;
+
+    // One item every 10 seconds, with nothing between 30 and 80 seconds.
+    let items = stream::iter([1, 2, 3, 7, 8]).then(|n| async move {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        if n == 7 {
+            tokio::time::sleep(Duration::from_secs(40)).await;
+        }
+        n
+    });
+    let outputs = Box::pin(items)
+        .complete_every(TestWriter::default(), Duration::from_secs(25))
+        .collect::<Vec<_>>()
+        .await;
+    // The window from 50 to 75 seconds was empty.
+    assert_eq!(outputs, vec![Ok(vec![1, 2]), Ok(vec![3]), Ok(vec![7, 8])]);
+
+    let outputs = stream::iter(1..=7)
+        .complete_when_or_after(
+            TestWriter::default(),
+            |ret| ret % 3 == 0,
+            Duration::from_secs(25),
+        )
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(
+        outputs,
+        vec![Ok(vec![1, 2, 3]), Ok(vec![4, 5, 6]), Ok(vec![7])]
+    );
+
+    // A writer that is terminated by an error ends the stream.
+    let fail_on_3 = |n: usize| {
+        if n == 3 { Err("bad part".to_string()) } else { Ok(n) }
+    };
+    let writer =
+        TestWriter::default().try_map_part(fail_on_3).poison_on_error();
+    let outputs = stream::iter(1..=5)
+        .complete_every(writer, Duration::from_secs(25))
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(outputs, vec![Err("bad part".to_string())]);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn complete_on_idle_stream() {
+    use std::time::Duration;
+
+    use futures::stream;
+
+    let (tx, rx) = futures::channel::mpsc::unbounded::<usize>();
+    let mut outputs =
+        rx.complete_on_idle(TestWriter::default(), Duration::from_secs(10));
+
+    tx.unbounded_send(1).unwrap();
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    tx.unbounded_send(2).unwrap();
+    // The gap started over with the second item, so the output is ready 10
+    // seconds after it even though the stream has not ended.
+    let start = tokio::time::Instant::now();
+    let out = outputs.next().await;
+    assert_eq!(out, Some(Ok(vec![1, 2])));
+    assert_eq!(start.elapsed(), Duration::from_secs(10));
+
+    tx.unbounded_send(3).unwrap();
+    drop(tx);
+    let rest = outputs.collect::<Vec<_>>().await;
+    assert_eq!(rest, vec![Ok(vec![3])]);
+
+    let outputs = stream::iter(1..=3)
+        .complete_on_idle(TestWriter::default(), Duration::from_secs(10))
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(outputs, vec![Ok(vec![1, 2, 3])]);
+
+    // Items that are further apart than the gap each get their own output.
+    let items = stream::iter([1, 2]).then(|n| async move {
+        tokio::time::sleep(Duration::from_secs(15)).await;
+        n
+    });
+    let outputs = Box::pin(items)
+        .complete_on_idle(TestWriter::default(), Duration::from_secs(10))
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(outputs, vec![Ok(vec![1]), Ok(vec![2])]);
+
+    // A writer that is terminated by an error ends the stream.
+    let fail_on_2 = |n: usize| {
+        if n == 2 { Err("bad part".to_string()) } else { Ok(n) }
+    };
+    let writer =
+        TestWriter::default().try_map_part(fail_on_2).poison_on_error();
+    let outputs = stream::iter(1..=3)
+        .complete_on_idle(writer, Duration::from_secs(10))
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(outputs, vec![Err("bad part".to_string())]);
+}
+
+#[tokio::test]
+async fn abort_writer() {
+    // Buffered parts are discarded and the abort reaches the inner writer.
+    let mut inner = TestWriter::default();
+    let mut writer = (&mut inner).map_part(|n: usize| n * 2).buffered(2);
+    writer.send_flush(1).await.unwrap();
+    writer.feed(2).await.unwrap();
+    writer.abort().await.unwrap();
+    writer.send_flush(3).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![6]);
+    assert_eq!(inner.aborted, 1);
+
+    let fail_on_3 = |n: usize| {
+        if n == 3 { Err("bad part".to_string()) } else { Ok(n) }
+    };
+
+    let mut inner = TestWriter::default();
+    let res = iter(1..=5)
+        .complete_with((&mut inner).try_map_part(fail_on_3))
+        .abort_on_error()
+        .await;
+    assert_eq!(res, Err("bad part".to_string()));
+    assert_eq!(inner.aborted, 1);
+    assert!(inner.inner.is_empty());
+
+    // The stream goes on with a new output after the writer is aborted.
+    let mut inner = TestWriter::default();
+    let outputs = iter(1..=6)
+        .try_complete_when((&mut inner).try_map_part(fail_on_3), |n| n % 2 == 0)
+        .abort_on_error()
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(
+        outputs,
+        vec![
+            Ok(vec![1, 2]),
+            Err("bad part".to_string()),
+            Ok(vec![4]),
+            Ok(vec![5, 6])
+        ]
+    );
+    assert_eq!(inner.aborted, 1);
+}
+
+#[tokio::test]
+async fn guarded_writer() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use multipart_write::write::DropPolicy;
+
+    let cleaned = Arc::new(AtomicUsize::new(0));
+    let policy = |cleaned: &Arc<AtomicUsize>| {
+        let cleaned = Arc::clone(cleaned);
+        DropPolicy::cleanup(move |writer: Pin<Box<TestWriter>>| {
+            cleaned.fetch_add(writer.inner.len(), Ordering::SeqCst);
+        })
+    };
+
+    // Nothing happens if the write was not in progress.
+    let writer = TestWriter::default().guarded(policy(&cleaned));
+    drop(writer);
+    let mut writer = TestWriter::default().guarded(policy(&cleaned));
+    writer.send_flush(1).await.unwrap();
+    assert!(writer.in_progress());
+    writer.complete().await.unwrap();
+    assert!(!writer.in_progress());
+    drop(writer);
+    assert_eq!(cleaned.load(Ordering::SeqCst), 0);
+
+    let mut writer = TestWriter::default().guarded(policy(&cleaned));
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(2).await.unwrap();
+    drop(writer);
+    assert_eq!(cleaned.load(Ordering::SeqCst), 2);
+
+    static LOGGED: AtomicUsize = AtomicUsize::new(0);
+    let log = |msg: &str| {
+        assert!(msg.contains("TestWriter"));
+        LOGGED.fetch_add(1, Ordering::SeqCst);
+    };
+    let mut writer = TestWriter::default().guarded(DropPolicy::Log(log));
+    writer.send_flush(1).await.unwrap();
+    drop(writer);
+    assert_eq!(LOGGED.load(Ordering::SeqCst), 1);
+
+    let mut writer = TestWriter::default().guarded(DropPolicy::Panic);
+    writer.send_flush(1).await.unwrap();
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        drop(writer);
+    }));
+    assert!(res.is_err());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn guarded_writer_spawn_abort() {
+    use multipart_write::write::DropPolicy;
+
+    let shared = SharedWriter::default();
+    let mut writer = shared.clone().guarded(DropPolicy::spawn_abort());
+    writer.send_flush(1).await.unwrap();
+    drop(writer);
+    tokio::task::yield_now().await;
+    assert_eq!(shared.0.lock().unwrap().aborted, 1);
+
+    // Failing to complete leaves the write in progress.
+    let mut writer = FlakyWriter::new(0, 1).guarded(DropPolicy::Log(|_| {}));
+    writer.send_flush(1).await.unwrap();
+    assert!(writer.complete().await.is_err());
+    assert!(writer.in_progress());
+    assert_eq!(writer.complete().await.unwrap(), vec![1]);
+    assert!(!writer.in_progress());
+}
+
+#[tokio::test]
+async fn transaction_writer() {
+    use multipart_write::write::{self, TransactionPhase};
+
+    type Commit = fn(
+        Result<Vec<usize>, String>,
+    ) -> future::Ready<Result<Vec<usize>, String>>;
+    let ok: Commit = future::ready;
+    let fail: Commit = |_| future::ready(Err("commit failed".to_string()));
+
+    let (mut a, mut b, mut c): (TestWriter, TestWriter, TestWriter) =
+        Default::default();
+    let mut writer = write::transaction([
+        (&mut a).then(ok),
+        (&mut b).then(ok),
+        (&mut c).then(ok),
+    ]);
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(2).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![vec![1, 2], vec![1, 2], vec![1, 2]]);
+
+    // Every branch but the one that failed is aborted.
+    let (mut a, mut b, mut c): (TestWriter, TestWriter, TestWriter) =
+        Default::default();
+    let mut writer = write::transaction([
+        (&mut a).then(ok),
+        (&mut b).then(fail),
+        (&mut c).then(ok),
+    ]);
+    writer.send_flush(1).await.unwrap();
+    let e = writer.complete().await.unwrap_err();
+    assert_eq!((e.branch(), e.phase()), (1, TransactionPhase::Commit));
+    assert_eq!(e.to_string(), "branch 1 failed to commit: commit failed");
+    // The first branch was already completed, so it cannot be rolled back.
+    assert_eq!(e.committed(), &[0]);
+    drop(writer);
+    assert_eq!((a.aborted, b.aborted, c.aborted), (0, 1, 1));
+
+    let fail_on_3 = |n: usize| {
+        if n == 3 { Err("bad part".to_string()) } else { Ok(n) }
+    };
+
+    let (mut a, mut b): (TestWriter, TestWriter) = Default::default();
+    let mut writer = write::transaction([
+        (&mut a).try_map_part(fail_on_3),
+        (&mut b).try_map_part(fail_on_3),
+    ]);
+    writer.send_flush(1).await.unwrap();
+    let e = writer.send_flush(3).await.unwrap_err();
+    assert_eq!((e.branch(), e.phase()), (0, TransactionPhase::Write));
+
+    // The transaction is poisoned until it is aborted.
+    assert!(writer.is_poisoned());
+    let e = writer.send_flush(4).await.unwrap_err();
+    assert_eq!((e.branch(), e.phase()), (0, TransactionPhase::Write));
+    assert!(writer.complete().await.is_err());
+    writer.abort().await.unwrap();
+    writer.send_flush(4).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![vec![4], vec![4]]);
+    drop(writer);
+    assert_eq!((a.aborted, b.aborted), (2, 2));
+
+    // Every branch is aborted even if one fails to, and the writer stays
+    // poisoned until they all are.
+    let writers = [AbortWriter::new(2, false), AbortWriter::new(0, true)];
+    let mut writer = write::transaction(writers);
+    writer.send_flush(1).await.unwrap();
+    let e = writer.abort().await.unwrap_err();
+    assert_eq!((e.branch(), e.phase()), (1, TransactionPhase::Abort));
+    assert_eq!(writer.get_ref()[0].inner.aborted, 1);
+    assert!(writer.is_poisoned());
+}
+
+#[tokio::test]
+async fn poison_writer() {
+    let fail_on_3 = |n: usize| {
+        if n == 3 { Err("bad part".to_string()) } else { Ok(n) }
+    };
+
+    let mut writer =
+        TestWriter::default().try_map_part(fail_on_3).poison_on_error();
+    writer.send_flush(1).await.unwrap();
+    assert!(writer.send_flush(3).await.is_err());
+    assert!(writer.is_poisoned());
+    assert_eq!(writer.send_flush(4).await, Err("bad part".to_string()));
+    assert_eq!(writer.complete().await, Err("bad part".to_string()));
+    assert_eq!(writer.get_ref().get_ref().inner, vec![1]);
+
+    // The stream ends after the error instead of going on.
+    let outputs = iter(1..=6)
+        .try_complete_when(
+            TestWriter::default().try_map_part(fail_on_3).poison_on_error(),
+            |n| n % 2 == 0,
+        )
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(outputs, vec![Ok(vec![1, 2]), Err("bad part".to_string())]);
+}
+
+#[tokio::test]
+async fn dead_letter_writer() {
+    let check = |n: usize| match n {
+        3 => Err("bad part".to_string()),
+        5 => Err("fatal".to_string()),
+        _ => Ok(n),
+    };
+
+    let mut dlq = TestWriter::default();
+    let mut writer = TestWriter::default()
+        .try_map_part(check)
+        .dead_letter((&mut dlq).map_part(|(n, _): (usize, String)| n), |e| {
+            e != "fatal"
+        });
+    writer.send_flush(1).await.unwrap();
+    assert_eq!(writer.send_flush(3).await, Ok(None));
+    writer.send_flush(4).await.unwrap();
+    assert_eq!(writer.send_flush(5).await, Err("fatal".to_string()));
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, (vec![1, 4], vec![3]));
+
+    writer.send_flush(3).await.unwrap();
+    writer.abort().await.unwrap();
+    drop(writer);
+    assert_eq!(dlq.aborted, 1);
+    assert!(dlq.inner.is_empty());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn circuit_breaker_writer() {
+    use std::io;
+    use std::time::Duration;
+
+    use multipart_write::write::{
+        CircuitBreakerConfig, CircuitOpen, CircuitState,
+    };
+    use tokio::time::Instant;
+
+    let fail_on_0 = |n: usize| {
+        if n == 0 { Err(io::Error::other("bad part")) } else { Ok(n) }
+    };
+    let config = CircuitBreakerConfig::new()
+        .failure_rate(0.5)
+        .window(4)
+        .min_calls(4)
+        .open_for(Duration::from_secs(10));
+
+    let mut writer = TestWriter::default()
+        .map_err(io::Error::other)
+        .try_map_part(fail_on_0)
+        .circuit_breaker(config);
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(0).await.unwrap_err();
+    assert_eq!(writer.state(), CircuitState::Closed);
+    writer.send_flush(0).await.unwrap_err();
+    assert_eq!(writer.state(), CircuitState::Open);
+
+    let e = writer.send_flush(1).await.unwrap_err();
+    let open = *e.into_inner().unwrap().downcast::<CircuitOpen>().unwrap();
+    assert_eq!(open.retry_after(), Duration::from_secs(10));
+
+    // The circuit opens again if the writer still fails when it is tried.
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    writer.send_flush(0).await.unwrap_err();
+    assert_eq!(writer.state(), CircuitState::Open);
+
+    // Sending a part is enough to close it.
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    writer.feed(2).await.unwrap();
+    assert_eq!(writer.state(), CircuitState::Closed);
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, vec![1, 2]);
+
+    let mut writer = TestWriter::default()
+        .map_err(io::Error::other)
+        .try_map_part(fail_on_0)
+        .circuit_breaker(config.wait_while_open());
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(0).await.unwrap_err();
+    writer.send_flush(0).await.unwrap_err();
+    let start = Instant::now();
+    writer.send_flush(2).await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(10));
+    assert_eq!(writer.state(), CircuitState::Closed);
+
+    // More results than the window holds are never needed to open it.
+    let config = CircuitBreakerConfig::new().window(4);
+    let mut writer = TestWriter::default()
+        .map_err(io::Error::other)
+        .try_map_part(fail_on_0)
+        .circuit_breaker(config);
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(0).await.unwrap_err();
+    writer.send_flush(0).await.unwrap_err();
+    assert_eq!(writer.state(), CircuitState::Open);
+}
+
+#[tokio::test]
+async fn or_else_switch_writer() {
+    use multipart_write::Either;
+
+    let fail_on_3 = |n: usize| {
+        if n == 3 { Err("bad part".to_string()) } else { Ok(n) }
+    };
+    let make_fallback = || future::ready(Ok(TestWriter::new(10)));
+
+    let mut writer = TestWriter::default()
+        .try_map_part(fail_on_3)
+        .or_else_switch(make_fallback);
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(2).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, Either::Left(vec![1, 2]));
+
+    // The parts of the write are replayed to the fallback writer.
+    writer.feed(1).await.unwrap();
+    writer.feed(2).await.unwrap();
+    writer.send_flush(3).await.unwrap();
+    assert!(writer.is_switched());
+    assert_eq!(Pin::new(&mut writer).take_replayed(), vec![1, 2, 3]);
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, Either::Right(vec![10, 20, 30]));
+    let ret = writer.send_flush(4).await.unwrap();
+    assert_eq!(ret, Some(Either::Right(4)));
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, Either::Right(vec![40]));
+
+    let mut writer = TestWriter::default()
+        .try_map_part(fail_on_3)
+        .or_else_switch(make_fallback)
+        .replay_capacity(1);
+    writer.feed(1).await.unwrap();
+    assert!(writer.is_replayable());
+    writer.feed(2).await.unwrap();
+    assert!(!writer.is_replayable());
+    let res = writer.send_flush(3).await;
+    assert_eq!(res, Err("bad part".to_string()));
+    assert!(!writer.is_switched());
+
+    // Flushed parts are not replayed.
+    let mut writer = TestWriter::default()
+        .try_map_part(fail_on_3)
+        .or_else_switch(make_fallback)
+        .replay_capacity(1);
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(2).await.unwrap();
+    writer.send_flush(3).await.unwrap();
+    let out = writer.complete().await.unwrap();
+    assert_eq!(out, Either::Right(vec![30]));
+
+    // The primary writer can be aborted when switching, and aborting the
+    // writer then only aborts the fallback writer.
+    let mut writer = TestWriter::default()
+        .try_map_part(fail_on_3)
+        .or_else_switch(make_fallback)
+        .abort_on_switch();
+    writer.send_flush(1).await.unwrap();
+    writer.send_flush(3).await.unwrap();
+    assert_eq!(writer.get_ref().get_ref().aborted, 1);
+    writer.abort().await.unwrap();
+    let aborted = writer.fallback_ref().map(|fallback| fallback.aborted);
+    assert_eq!((writer.get_ref().get_ref().aborted, aborted), (1, Some(1)));
+}